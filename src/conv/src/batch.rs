@@ -0,0 +1,92 @@
+//! Converts many `VALUE TARGET_UNIT` lines in one go (e.g. from stdin or a file).
+use super::expr;
+use super::output::Printer;
+use std::io::{self, BufRead, Write};
+
+/// Converts each line of the input, printing the results in order. Empty lines
+/// and comments (lines starting with `#`) are skipped.
+///
+/// Failures are printed along with their line number and processing continues
+/// with the next line, unless `fail_fast` is set. When humanize is set, each
+/// result is converted into its most readable unit. Returns false if any line
+/// failed to convert.
+pub fn run<O: Write, E: Write>(
+    input: impl BufRead,
+    printer: &mut Printer<O, E>,
+    fail_fast: bool,
+    humanize: bool,
+) -> io::Result<bool> {
+    let mut ok = true;
+    for (n, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let res = expr::convert(line)
+            .and_then(|res| expr::humanize(res, humanize))
+            .map_err(|e| format!("line {}: {}", n + 1, e));
+        printer.print(&res)?;
+
+        if res.is_err() {
+            ok = false;
+            if fail_fast {
+                break;
+            }
+        }
+    }
+
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::Format;
+
+    #[test]
+    fn converts_each_line() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err);
+
+        let input = "# lengths\n10km m\n\n1m cm\n";
+        assert!(run(input.as_bytes(), &mut printer, false, false).unwrap());
+        assert_eq!("10000.00m\n100.00cm\n", String::from_utf8(out).unwrap());
+        assert!(err.is_empty());
+    }
+
+    #[test]
+    fn humanizes_each_line() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err);
+
+        assert!(run("1500m m\n0.01m m\n".as_bytes(), &mut printer, false, true).unwrap());
+        assert_eq!("1.50km\n1.00cm\n", String::from_utf8(out).unwrap());
+        assert!(err.is_empty());
+    }
+
+    #[test]
+    fn continues_after_errors() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err);
+
+        assert!(!run("abc m\n10km m\n".as_bytes(), &mut printer, false, false).unwrap());
+        assert_eq!("10000.00m\n", String::from_utf8(out).unwrap());
+        assert_eq!("line 1: invalid value\n", String::from_utf8(err).unwrap());
+    }
+
+    #[test]
+    fn fail_fast() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err);
+
+        let input = "10km m\n10km\n1m cm\n";
+        assert!(!run(input.as_bytes(), &mut printer, true, false).unwrap());
+        assert_eq!("10000.00m\n", String::from_utf8(out).unwrap());
+        assert_eq!(
+            "line 2: expected VALUE TARGET_UNIT, got: 10km\n",
+            String::from_utf8(err).unwrap()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Generates static shell completion scripts.
+//!
+//! Options are taken from the clap command definition and positional arguments
+//! complete to every known unit symbol. Completing only the units that are
+//! compatible with the `from` argument would require dynamic completion.
+use clap::ValueEnum;
+
+/// The shells completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    Powershell,
+}
+
+/// A command line option and its help text.
+struct Opt {
+    flag: String,
+    help: String,
+}
+
+/// Returns the completion script for the given shell.
+pub fn generate(shell: Shell, cmd: &mut clap::Command) -> String {
+    cmd.build();
+    let name = cmd.get_name().to_string();
+    let opts = options(cmd);
+    let units = units();
+
+    match shell {
+        Shell::Bash => bash(&name, &opts, &units),
+        Shell::Zsh => zsh(&name, &opts, &units),
+        Shell::Fish => fish(&name, &opts, &units),
+        Shell::Elvish => elvish(&name, &opts, &units),
+        Shell::Powershell => powershell(&name, &opts, &units),
+    }
+}
+
+fn options(cmd: &clap::Command) -> Vec<Opt> {
+    cmd.get_arguments()
+        .filter(|a| !a.is_positional() && !a.is_hide_set())
+        .flat_map(|a| {
+            let help = a.get_help().map(|h| h.to_string()).unwrap_or_default();
+            let long = a.get_long().map(|l| format!("--{}", l));
+            let short = a.get_short().map(|s| format!("-{}", s));
+
+            long.into_iter().chain(short).map(move |flag| Opt {
+                flag,
+                help: help.clone(),
+            })
+        })
+        .collect()
+}
+
+fn units() -> Vec<String> {
+    core::all_unit_symbols()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn flags(opts: &[Opt]) -> String {
+    opts.iter()
+        .map(|o| o.flag.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bash(name: &str, opts: &[Opt], units: &[String]) -> String {
+    format!(
+        r#"_{name}() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{opts}" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "{units}" -- "$cur"))
+    fi
+}}
+
+complete -F _{name} -o default {name}
+"#,
+        opts = flags(opts),
+        units = units.join(" "),
+    )
+}
+
+fn zsh(name: &str, opts: &[Opt], units: &[String]) -> String {
+    let specs: Vec<String> = opts
+        .iter()
+        .map(|o| {
+            let help = o.help.replace('\'', "'\\''").replace(['[', ']'], "");
+            format!("        '{}[{}]' \\", o.flag, help)
+        })
+        .collect();
+
+    format!(
+        r#"#compdef {name}
+
+_{name}() {{
+    _arguments \
+{specs}
+        '1:from:({units})' \
+        '2:to_unit:({units})'
+}}
+
+_{name} "$@"
+"#,
+        specs = specs.join("\n"),
+        units = units.join(" "),
+    )
+}
+
+fn fish(name: &str, opts: &[Opt], units: &[String]) -> String {
+    let mut lines: Vec<String> = opts
+        .iter()
+        .map(|o| {
+            let flag = match o.flag.strip_prefix("--") {
+                Some(long) => format!("-l {}", long),
+                None => format!("-s {}", &o.flag[1..]),
+            };
+            format!(
+                "complete -c {} {} -d '{}'",
+                name,
+                flag,
+                o.help.replace('\'', "\\'")
+            )
+        })
+        .collect();
+
+    lines.push(format!("complete -c {} -f -a '{}'", name, units.join(" ")));
+    lines.join("\n") + "\n"
+}
+
+fn elvish(name: &str, opts: &[Opt], units: &[String]) -> String {
+    format!(
+        r#"set edit:completion:arg-completer[{name}] = {{|@words|
+    var current = $words[-1]
+    if (str:has-prefix $current -) {{
+        put {opts}
+    }} else {{
+        put {units}
+    }}
+}}
+"#,
+        opts = flags(opts),
+        units = units.join(" "),
+    )
+}
+
+fn powershell(name: &str, opts: &[Opt], units: &[String]) -> String {
+    let quote = |words: Vec<&str>| {
+        words
+            .iter()
+            .map(|w| format!("'{}'", w))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        r#"Register-ArgumentCompleter -Native -CommandName '{name}' -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $candidates = if ($wordToComplete -like '-*') {{ @({opts}) }} else {{ @({units}) }}
+    $candidates | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_)
+    }}
+}}
+"#,
+        opts = quote(opts.iter().map(|o| o.flag.as_str()).collect()),
+        units = quote(units.iter().map(|u| u.as_str()).collect()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd() -> clap::Command {
+        clap::Command::new("conv").arg(clap::Arg::new("from")).arg(
+            clap::Arg::new("json")
+                .long("json")
+                .help("Print the output as JSON")
+                .action(clap::ArgAction::SetTrue),
+        )
+    }
+
+    #[test]
+    fn includes_options_and_units() {
+        for shell in Shell::value_variants() {
+            let script = generate(*shell, &mut cmd());
+            assert!(script.contains("json"), "{:?}", shell);
+            assert!(script.contains("nmi"), "{:?}", shell);
+        }
+    }
+
+    #[test]
+    fn fish() {
+        let script = generate(Shell::Fish, &mut cmd());
+        assert!(script.contains("complete -c conv -l json -d 'Print the output as JSON'\n"));
+        assert!(script.contains("complete -c conv -s h -d 'Print help'\n"));
+    }
+}
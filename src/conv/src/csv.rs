@@ -0,0 +1,67 @@
+//! Renders conversions as CSV rows (RFC 4180) for data pipelines and spreadsheets.
+
+/// The header row describing the columns produced by `row`.
+pub const HEADER: &str = "from_quantity,from_unit,to_quantity,to_unit";
+
+/// Returns the CSV row for a conversion (e.g. `100,c,212,f`).
+///
+/// When given, the converted quantity is rounded to `precision` decimal places.
+pub fn row(res: &core::ConversionResult, precision: Option<usize>) -> String {
+    let to_quantity = match precision {
+        Some(p) => format!("{:.*}", p, res.to.quantity),
+        None => res.to.quantity.to_string(),
+    };
+
+    [
+        res.from.quantity.to_string(),
+        field(&res.from.unit),
+        to_quantity,
+        field(&res.to.unit),
+    ]
+    .join(",")
+}
+
+/// Quotes the field when it contains a delimiter, quote or line break. Quotes
+/// within the field are escaped by doubling them.
+fn field(s: &str) -> String {
+    if s.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_rows() {
+        let res = core::ConversionResult {
+            from: core::Value::new(10.0, "km"),
+            to: core::Value::new(10000.5, "m"),
+        };
+
+        assert_eq!("10,km,10000.5,m", row(&res, None));
+        assert_eq!("10,km,10000.500,m", row(&res, Some(3)));
+    }
+
+    #[test]
+    fn quotes_fields() {
+        assert_eq!("km", field("km"));
+        assert_eq!("\"a,b\"", field("a,b"));
+        assert_eq!("\"say \"\"hi\"\"\"", field("say \"hi\""));
+        assert_eq!("\"a\nb\"", field("a\nb"));
+    }
+
+    #[test]
+    fn quotes_units_in_rows() {
+        // None of the built-in units need quoting, but custom ones might.
+        let res = core::ConversionResult {
+            from: core::Value::new(24.0, "\""),
+            to: core::Value::new(2.0, "ft,in"),
+        };
+
+        assert_eq!(r#"24,"""",2,"ft,in""#, row(&res, None));
+    }
+}
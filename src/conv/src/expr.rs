@@ -0,0 +1,66 @@
+//! Evaluates conversion expressions like `100c f` (see `core::parse_and_convert`).
+
+/// Converts an expression of the form `VALUE TARGET_UNIT`. The last token is
+/// the target unit and everything before it is the value (so `100 c f` works
+/// as well).
+pub fn convert(line: &str) -> Result<core::ConversionResult, String> {
+    core::parse_and_convert_paired(line).map_err(|e| match e {
+        // Parse errors are shown as is, without the "failed to parse" prefix.
+        core::ConversionError::Parse(err) => err.to_string(),
+        err => err.to_string(),
+    })
+}
+
+/// Converts the value (e.g. `100c`) into the target unit.
+pub fn convert_parts(from: &str, to_unit: &str) -> Result<core::ConversionResult, String> {
+    convert(&format!("{} {}", from, to_unit))
+}
+
+/// Converts the result into its most readable unit (see `core::humanize`) when
+/// enabled, keeping the original value. Otherwise the result is returned as is.
+pub fn humanize(
+    res: core::ConversionResult,
+    enabled: bool,
+) -> Result<core::ConversionResult, String> {
+    if !enabled {
+        return Ok(res);
+    }
+
+    core::humanize(res.to)
+        .map(|to| core::ConversionResult { from: res.from, to })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_expressions() {
+        assert_eq!("10000.00m", convert("10km m").unwrap().to.to_string());
+        assert_eq!("10000.00m", convert("  10 km   m ").unwrap().to.to_string());
+        assert_eq!(
+            "10.00km",
+            convert_parts("10 km", "m").unwrap().from.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        assert_eq!(
+            Err("expected VALUE TARGET_UNIT, got: 10km".into()),
+            convert("10km")
+        );
+        assert_eq!(Err("invalid value".into()), convert("abc m"));
+        assert_eq!(Err("unknown unit: xyz".into()), convert("1xyz m"));
+    }
+
+    #[test]
+    fn humanizes_results() {
+        let res = convert("1500m m").and_then(|res| humanize(res, true));
+        assert_eq!("1500.00m = 1.50km", res.unwrap().to_string());
+
+        let res = convert("1500m m").and_then(|res| humanize(res, false));
+        assert_eq!("1500.00m = 1500.00m", res.unwrap().to_string());
+    }
+}
@@ -0,0 +1,132 @@
+//! Renders CLI output as JSON for scripts and tools like Alfred.
+//!
+//! The output is small and well-defined, so it's built by hand rather than
+//! pulling in a serialization framework.
+use std::collections::BTreeMap;
+
+/// Returns the JSON representation of a conversion (e.g.
+/// `{"from": {"quantity": 100, "unit": "c"}, "to": {"quantity": 212, "unit": "f"}}`).
+///
+/// When given, the converted quantity is rounded to `precision` decimal places.
+pub fn conversion(res: &core::ConversionResult, precision: Option<usize>) -> String {
+    format!(
+        r#"{{"from": {}, "to": {}}}"#,
+        value(&res.from, None),
+        value(&res.to, precision)
+    )
+}
+
+/// Returns the JSON representation of many conversions as a single array of
+/// conversion objects (see `conversion`).
+pub fn conversions(results: &[core::ConversionResult], precision: Option<usize>) -> String {
+    let results: Vec<String> = results.iter().map(|r| conversion(r, precision)).collect();
+    format!("[{}]", results.join(", "))
+}
+
+/// Returns the JSON representation of the available units as an array of
+/// family objects.
+pub fn units(units: &BTreeMap<&str, &[core::Unit]>) -> String {
+    let families: Vec<String> = units
+        .iter()
+        .map(|(id, units)| {
+            let units: Vec<String> = units.iter().map(unit).collect();
+            format!(
+                r#"{{"id": {}, "units": [{}]}}"#,
+                string(id),
+                units.join(", ")
+            )
+        })
+        .collect();
+
+    format!("[{}]", families.join(", "))
+}
+
+/// Returns the JSON representation of the family IDs as an array of strings.
+pub fn families(ids: &[&str]) -> String {
+    let ids: Vec<String> = ids.iter().map(|id| string(id)).collect();
+    format!("[{}]", ids.join(", "))
+}
+
+/// Returns a JSON object describing an error (e.g. `{"error": "invalid value"}`).
+pub fn error(msg: &str) -> String {
+    format!(r#"{{"error": {}}}"#, string(msg))
+}
+
+fn value(v: &core::Value, precision: Option<usize>) -> String {
+    format!(
+        r#"{{"quantity": {}, "unit": {}}}"#,
+        number(v.quantity, precision),
+        string(&v.unit)
+    )
+}
+
+fn unit(u: &core::Unit) -> String {
+    let names: Vec<String> = u.names.iter().map(|n| string(n)).collect();
+    format!(
+        r#"{{"symbol": {}, "names": [{}]}}"#,
+        string(&u.symbol),
+        names.join(", ")
+    )
+}
+
+/// Returns the JSON representation of a number. JSON has no representation for
+/// NaN or infinity, so they're written as null.
+pub fn number(n: f64, precision: Option<usize>) -> String {
+    match precision {
+        _ if !n.is_finite() => "null".into(),
+        Some(p) => format!("{:.*}", p, n),
+        None => n.to_string(),
+    }
+}
+
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(r#""plain""#, string("plain"));
+        assert_eq!(r#""say \"hi\"\n""#, string("say \"hi\"\n"));
+        assert_eq!(r#""a\\b\u0001""#, string("a\\b\u{1}"));
+    }
+
+    #[test]
+    fn families_array() {
+        assert_eq!("[]", families(&[]));
+        assert_eq!(
+            r#"["Lengths", "Temperature"]"#,
+            families(&["Lengths", "Temperature"])
+        );
+    }
+
+    #[test]
+    fn non_finite_numbers_are_null() {
+        assert_eq!("1.5", number(1.5, None));
+        assert_eq!("null", number(f64::INFINITY, None));
+        assert_eq!("null", number(f64::NAN, Some(2)));
+    }
+
+    #[test]
+    fn rounds_numbers() {
+        assert_eq!("2", number(1.5, Some(0)));
+        assert_eq!("1.500", number(1.5, Some(3)));
+    }
+}
@@ -1,38 +1,449 @@
 extern crate clap;
 extern crate core;
 
-use clap::Parser;
+mod batch;
+mod completions;
+mod csv;
+mod expr;
+mod json;
+mod output;
+mod repl;
+mod style;
+#[cfg(unix)]
+mod watch;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use output::{Format, Printer};
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal, Stderr, Stdout};
+use std::path::PathBuf;
+use std::process;
+use style::Style;
 
 /// A simple little program to convert values between units.
 ///
 /// This can be useful as a CLI tool but can also be integrated with things like
 /// Alfred for example.
+///
+/// When no arguments are given, conversions (e.g. "100c f") are read from stdin,
+/// one per line.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
-    from: String,
-    to_unit: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    from: Option<String>,
+    to_unit: Option<String>,
 
     #[arg(short, long)]
     units: bool,
+
+    /// List the available families (without their units)
+    #[arg(long, conflicts_with = "units")]
+    families: bool,
+
+    /// Only list the units of this family (e.g. temperature)
+    #[arg(long, value_name = "NAME", requires = "units")]
+    family: Option<String>,
+
+    /// Read conversions (e.g. "100c f") interactively until EOF or "quit"
+    #[arg(long)]
+    repl: bool,
+
+    /// Convert the expression (e.g. "100c f") live as it's typed, until Enter or Ctrl+C
+    #[cfg(unix)]
+    #[arg(long, conflicts_with_all = ["from", "repl", "humanize"])]
+    watch: bool,
+
+    /// Read conversions (e.g. "100c f") from a file, one per line
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["from", "repl"])]
+    file: Option<PathBuf>,
+
+    /// Stop at the first line that fails to convert
+    #[arg(long, requires = "file")]
+    fail_fast: bool,
+
+    /// Print the completion script for the given shell
+    #[arg(long, value_enum, value_name = "SHELL")]
+    completions: Option<completions::Shell>,
+
+    /// Print the output as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Print the output as CSV
+    #[arg(long, global = true, conflicts_with = "json")]
+    csv: bool,
+
+    /// Omit the header row from CSV output
+    #[arg(long, global = true, requires = "csv")]
+    no_header: bool,
+
+    /// Number of decimal places to print (defaults to 2, or full precision for JSON and CSV)
+    #[arg(short, long, global = true, value_name = "N")]
+    precision: Option<u32>,
+
+    /// Only print the converted quantity, without the unit
+    #[arg(short, long, global = true, conflicts_with = "csv")]
+    quiet: bool,
+
+    /// Print the result in its most readable unit (e.g. 1.50km rather than 1500.00m)
+    #[arg(long, global = true)]
+    humanize: bool,
+
+    /// Highlight the output with colors (default when printing to a terminal)
+    #[arg(long, global = true, overrides_with = "no_color")]
+    color: bool,
+
+    /// Never highlight the output with colors
+    #[arg(long, global = true, overrides_with = "color")]
+    no_color: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert a value into another unit (the default when no command is given)
+    Convert {
+        from: String,
+        to_unit: String,
+
+        /// Print the original value along with the converted one
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// List the available units
+    List {
+        /// Only list the units of this family (e.g. temperature)
+        #[arg(long, value_name = "NAME")]
+        family: Option<String>,
+    },
+    /// Convert a value into every other unit of its family
+    All { from: String },
+    /// Check whether a unit is known
+    Check { unit: String },
+    /// Print a quantity of a unit converted into every other unit of its family
+    Cheatsheet {
+        unit: String,
+
+        /// The quantity to convert
+        #[arg(default_value_t = 1.0, allow_negative_numbers = true)]
+        quantity: f64,
+    },
+    /// Describe a unit (names, family, ratio, etc.) and how it relates to its
+    /// family's base unit
+    Info { unit: String },
+}
+
+impl Args {
+    fn format(&self) -> Format {
+        match (self.json, self.csv) {
+            (true, _) => Format::Json,
+            (_, true) => Format::Csv {
+                header: !self.no_header,
+            },
+            _ => Format::Text,
+        }
+    }
+
+    fn style(&self) -> Style {
+        Style::new(!self.no_color && (self.color || io::stdout().is_terminal()))
+    }
+
+    fn printer(&self) -> Printer<Stdout, Stderr> {
+        let precision = self.precision.map(|p| p as usize);
+        Printer::new(self.format(), precision, io::stdout(), io::stderr())
+            .with_style(self.style())
+            .with_quiet(self.quiet)
+    }
 }
 
 fn main() -> core::Result {
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Convert {
+            from,
+            to_unit,
+            verbose,
+        }) => {
+            let mut printer = args.printer().with_verbose(*verbose);
+            return convert(&mut printer, from, to_unit, args.humanize);
+        }
+        Some(Command::List { family }) => return list(&args, family.as_deref()),
+        Some(Command::All { from }) => return convert_all(&args, from),
+        Some(Command::Check { unit }) => return check(&args, unit),
+        Some(Command::Info { unit }) => return info(&args, unit),
+        Some(Command::Cheatsheet { unit, quantity }) => return cheatsheet(&args, unit, *quantity),
+        None => {}
+    }
+
+    if let Some(shell) = args.completions {
+        print!("{}", completions::generate(shell, &mut Args::command()));
+        return core::Value::ok();
+    }
+
+    if args.families {
+        let units = core::units();
+        let ids: Vec<&str> = units.keys().copied().collect();
+        if args.json {
+            println!("{}", json::families(&ids));
+        } else {
+            ids.iter().for_each(|id| println!("{}", id));
+        }
+
+        return core::Value::ok();
+    }
+
     if args.units {
-        println!("Available units");
-        core::units().iter().for_each(|(k, v)| {
-            println!("\n**{}:**", k);
-            v.iter()
-                .for_each(|u| println!("{} - {}", u.symbol, u.names[0]));
+        return list(&args, args.family.as_deref());
+    }
+
+    if args.repl {
+        repl::run(
+            io::stdin().lock(),
+            io::stdout(),
+            io::stderr(),
+            args.humanize,
+        )?;
+        return core::Value::ok();
+    }
+
+    #[cfg(unix)]
+    if args.watch {
+        watch::run(io::stdin().lock(), io::stdout())?;
+        return core::Value::ok();
+    }
+
+    let mut printer = args.printer();
+    if let Some(path) = &args.file {
+        let file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+
+        if !batch::run(
+            BufReader::new(file),
+            &mut printer,
+            args.fail_fast,
+            args.humanize,
+        )? {
+            process::exit(1);
+        }
+
+        return core::Value::ok();
+    }
+
+    match (&args.from, &args.to_unit) {
+        (Some(from), Some(to_unit)) => convert(&mut printer, from, to_unit, args.humanize),
+        (None, None) if !io::stdin().is_terminal() => {
+            if !batch::run(io::stdin().lock(), &mut printer, false, args.humanize)? {
+                process::exit(1);
+            }
+
+            core::Value::ok()
+        }
+        _ => Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "both <FROM> and <TO_UNIT> are required",
+            )
+            .exit(),
+    }
+}
+
+/// Converts a single value, exiting with a non-zero code on failure. When
+/// humanize is set, the result is converted into its most readable unit.
+fn convert(
+    printer: &mut Printer<Stdout, Stderr>,
+    from: &str,
+    to_unit: &str,
+    humanize: bool,
+) -> core::Result {
+    let res = expr::convert_parts(from, to_unit).and_then(|res| expr::humanize(res, humanize));
+    printer.print(&res)?;
+    if res.is_err() {
+        process::exit(1);
+    }
+
+    core::Value::ok()
+}
+
+/// Converts a value into every other unit of its family, one per line (or row).
+/// JSON output is a single array of conversions.
+fn convert_all(args: &Args, from: &str) -> core::Result {
+    if args.quiet {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--quiet can't be used with all",
+            )
+            .exit();
+    }
+
+    let mut printer = args.printer();
+    let res = from
+        .parse::<core::Value>()
+        .map_err(|e| e.to_string())
+        .and_then(|v| {
+            core::convert_all(v.clone())
+                .map(|all| (v, all))
+                .map_err(|e| e.to_string())
         });
 
+    match res {
+        Ok((v, all)) => {
+            let results: Vec<core::ConversionResult> = all
+                .into_iter()
+                .map(|to| core::ConversionResult {
+                    from: v.clone(),
+                    to,
+                })
+                .collect();
+            printer.print_all(&results)?;
+        }
+        Err(e) => {
+            printer.print(&Err(e))?;
+            process::exit(1);
+        }
+    }
+
+    core::Value::ok()
+}
+
+/// Lists the available units, optionally limited to a single family.
+fn list(args: &Args, family: Option<&str>) -> core::Result {
+    let mut units = core::units();
+    if let Some(family) = family {
+        if units.keys().any(|id| id.eq_ignore_ascii_case(family)) {
+            units.retain(|id, _| id.eq_ignore_ascii_case(family));
+        } else {
+            eprintln!("warning: unknown family: {}", family);
+        }
+    }
+
+    if args.json {
+        println!("{}", json::units(&units));
         return core::Value::ok();
     }
 
-    match args.from.parse() {
-        Ok(v) => println!("{}", core::convert(v, &args.to_unit)?),
-        Err(e) => println!("{}", e),
+    let style = args.style();
+    println!("Available units");
+    units.iter().for_each(|(k, v)| {
+        println!("\n**{}:**", k);
+
+        // Units are grouped by system, in the order the systems first appear.
+        let mut systems: Vec<(&str, Vec<&core::Unit>)> = vec![];
+        for u in v.iter() {
+            let system = u.system.as_deref().unwrap_or("other");
+            match systems.iter_mut().find(|(s, _)| *s == system) {
+                Some((_, units)) => units.push(u),
+                None => systems.push((system, vec![u])),
+            }
+        }
+
+        let grouped = systems.len() > 1;
+        for (system, units) in systems {
+            if grouped {
+                println!("_{}_", system);
+            }
+
+            units.iter().for_each(|u| {
+                let name = match &u.description {
+                    Some(desc) => format!("{} ({})", u.names[0], desc),
+                    None => u.names[0].clone(),
+                };
+                println!("{} - {}", style.bold(&u.symbol), style.dim(&name));
+            });
+        }
+    });
+
+    core::Value::ok()
+}
+
+/// Prints the quantity of the unit in every other unit of its family, exiting
+/// with a non-zero code when the unit isn't known.
+fn cheatsheet(args: &Args, unit: &str, quantity: f64) -> core::Result {
+    let style = args.style();
+    match core::conversions_for(unit, quantity) {
+        Ok(all) => {
+            println!(
+                "{} =",
+                style.bold(&core::Value::new(quantity, unit).with_precision(4))
+            );
+            all.iter().for_each(|(_, v)| println!("  {}", v));
+        }
+        Err(e) => {
+            eprintln!("{}", style.red(&e.to_string()));
+            process::exit(1);
+        }
+    }
+
+    core::Value::ok()
+}
+
+/// Prints a description of the unit, exiting with a non-zero code when the unit
+/// isn't known.
+fn info(args: &Args, unit: &str) -> core::Result {
+    let style = args.style();
+    let (u, fam) = match (
+        unit.parse::<core::Unit>(),
+        core::UnitRegistry::global().find(unit),
+    ) {
+        (Ok(u), Some(fam)) => (u, fam),
+        _ => {
+            eprintln!("{}", style.red(&format!("unit not found: {}", unit)));
+            let suggestions: Vec<String> = core::suggest_unit(unit)
+                .iter()
+                .map(|u| format!("{} ({})", u.symbol, u.names[0]))
+                .collect();
+            if !suggestions.is_empty() {
+                eprintln!("did you mean: {}?", suggestions.join(", "));
+            }
+            process::exit(1);
+        }
+    };
+
+    let to_base = core::convert(core::Value::one(&u.symbol), &fam.base_unit)?;
+    let from_base = core::convert(core::Value::one(&fam.base_unit), &u.symbol)?;
+    let base = fam.base_unit.to_lowercase();
+
+    println!("{} ({})", style.bold(&u.symbol), u.names[0]);
+    let rows = [
+        ("family", fam.id.clone()),
+        ("aliases", u.names.join(", ")),
+        ("description", u.description.clone().unwrap_or_default()),
+        ("ratio", u.ratio.to_string()),
+        ("difference", u.difference.to_string()),
+        (
+            "to base",
+            format!("1 {} = {} {}", u.symbol, to_base.quantity, base),
+        ),
+        (
+            "from base",
+            format!("1 {} = {} {}", base, from_base.quantity, u.symbol),
+        ),
+    ];
+    for (label, value) in rows.iter().filter(|(_, v)| !v.is_empty()) {
+        println!("  {:<12} {}", style.dim(&format!("{}:", label)), value);
+    }
+
+    core::Value::ok()
+}
+
+/// Prints the family and name of the unit, exiting with a non-zero code when
+/// the unit isn't known.
+fn check(args: &Args, unit: &str) -> core::Result {
+    let style = args.style();
+    match core::validate_unit(unit) {
+        Ok(u) => {
+            let family = core::UnitRegistry::global().find(&u.symbol);
+            let id = family.map_or("", |f| f.id.as_str());
+            println!("{} - {} ({})", style.bold(&u.symbol), u.names[0], id);
+        }
+        Err(err) => {
+            eprintln!("{}", style.red(&err.to_string()));
+            process::exit(1);
+        }
     }
 
     core::Value::ok()
@@ -0,0 +1,257 @@
+//! Prints conversion results in the format requested on the command line.
+use super::style::Style;
+use super::{csv, json};
+use std::io::{self, Write};
+
+/// The formats results can be printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv { header: bool },
+}
+
+/// Writes results to `out` and errors to `err`. JSON errors are written to
+/// `out` as error objects so consumers only need to read one stream.
+///
+/// Quantities are printed with two decimal places in text mode and with full
+/// precision otherwise, unless a precision is given.
+pub struct Printer<O, E> {
+    format: Format,
+    precision: Option<usize>,
+    style: Style,
+    quiet: bool,
+    verbose: bool,
+    out: O,
+    err: E,
+    wrote_header: bool,
+}
+
+impl<O: Write, E: Write> Printer<O, E> {
+    pub fn new(format: Format, precision: Option<usize>, out: O, err: E) -> Self {
+        Self {
+            format,
+            precision,
+            style: Style::default(),
+            quiet: false,
+            verbose: false,
+            out,
+            err,
+            wrote_header: false,
+        }
+    }
+
+    /// Sets the style used for text output. Other formats are never styled.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets whether only the converted quantity (without the unit) is printed.
+    /// This doesn't apply to CSV output.
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Sets whether the original value is printed along with the converted one
+    /// (e.g. `100.00c = 212.00f`). This only applies to text output.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Prints the result of a single conversion. The CSV header (when enabled)
+    /// is printed before the first row.
+    pub fn print(&mut self, res: &Result<core::ConversionResult, String>) -> io::Result<()> {
+        match (self.format, res) {
+            (Format::Text, Ok(res)) if self.quiet => {
+                let precision = self.precision.unwrap_or(2);
+                let qty = format!("{:.*}", precision, res.to.quantity);
+                writeln!(self.out, "{}", self.style.bold(&qty))
+            }
+            (Format::Text, Ok(res)) if self.verbose => {
+                let precision = self.precision.unwrap_or(2);
+                writeln!(
+                    self.out,
+                    "{} = {}",
+                    self.style.value(&res.from, precision),
+                    self.style.value(&res.to, precision)
+                )
+            }
+            (Format::Text, Ok(res)) => {
+                let precision = self.precision.unwrap_or(2);
+                writeln!(self.out, "{}", self.style.value(&res.to, precision))
+            }
+            (Format::Text, Err(e)) => writeln!(self.err, "{}", self.style.red(e)),
+            (Format::Json, Ok(res)) if self.quiet => {
+                writeln!(
+                    self.out,
+                    "{}",
+                    json::number(res.to.quantity, self.precision)
+                )
+            }
+            (Format::Json, Ok(res)) => {
+                writeln!(self.out, "{}", json::conversion(res, self.precision))
+            }
+            (Format::Json, Err(e)) => writeln!(self.out, "{}", json::error(e)),
+            (Format::Csv { header }, Ok(res)) => {
+                if header && !self.wrote_header {
+                    writeln!(self.out, "{}", csv::HEADER)?;
+                    self.wrote_header = true;
+                }
+                writeln!(self.out, "{}", csv::row(res, self.precision))
+            }
+            (_, Err(e)) => writeln!(self.err, "{}", e),
+        }
+    }
+
+    /// Prints the results of several conversions. JSON results are printed as a
+    /// single array (of quantities when quiet), while other formats print one
+    /// result per line (or row) like `print`.
+    pub fn print_all(&mut self, results: &[core::ConversionResult]) -> io::Result<()> {
+        match self.format {
+            Format::Json if self.quiet => {
+                let qtys: Vec<String> = results
+                    .iter()
+                    .map(|r| json::number(r.to.quantity, self.precision))
+                    .collect();
+                writeln!(self.out, "[{}]", qtys.join(", "))
+            }
+            Format::Json => writeln!(self.out, "{}", json::conversions(results, self.precision)),
+            _ => results.iter().try_for_each(|r| self.print(&Ok(r.clone()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(
+        format: Format,
+        precision: Option<usize>,
+        results: &[Result<core::ConversionResult, String>],
+    ) -> (String, String) {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(format, precision, &mut out, &mut err);
+        results.iter().for_each(|r| printer.print(r).unwrap());
+
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    fn results() -> Vec<Result<core::ConversionResult, String>> {
+        vec![
+            Err("invalid value".into()),
+            Ok(core::ConversionResult {
+                from: core::Value::new(10.0, "km"),
+                to: core::Value::new(10000.0, "m"),
+            }),
+        ]
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            ("10000.00m\n".into(), "invalid value\n".into()),
+            print(Format::Text, None, &results())
+        );
+        assert_eq!("10000.0m\n", print(Format::Text, Some(1), &results()).0);
+    }
+
+    #[test]
+    fn styled_text() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer =
+            Printer::new(Format::Text, None, &mut out, &mut err).with_style(Style::new(true));
+        results().iter().for_each(|r| printer.print(r).unwrap());
+
+        assert_eq!(
+            "\x1b[1m10000.00\x1b[0m\x1b[36mm\x1b[0m\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(
+            "\x1b[31minvalid value\x1b[0m\n",
+            String::from_utf8(err).unwrap()
+        );
+    }
+
+    #[test]
+    fn quiet() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err).with_quiet(true);
+        results().iter().for_each(|r| printer.print(r).unwrap());
+        assert_eq!("10000.00\n", String::from_utf8(out).unwrap());
+
+        let mut out = vec![];
+        let mut printer = Printer::new(Format::Json, None, &mut out, &mut err).with_quiet(true);
+        results().iter().for_each(|r| printer.print(r).unwrap());
+        assert_eq!(
+            "{\"error\": \"invalid value\"}\n10000\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn json() {
+        let (out, err) = print(Format::Json, None, &results());
+        assert_eq!(2, out.lines().count());
+        assert!(out.starts_with(r#"{"error": "invalid value"}"#));
+        assert_eq!("", err);
+    }
+
+    #[test]
+    fn json_all() {
+        let res = results()[1].clone().unwrap();
+        let results = vec![res.clone(), res];
+
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Json, None, &mut out, &mut err);
+        printer.print_all(&results).unwrap();
+        printer.print_all(&[]).unwrap();
+
+        let conversion =
+            r#"{"from": {"quantity": 10, "unit": "km"}, "to": {"quantity": 10000, "unit": "m"}}"#;
+        assert_eq!(
+            format!("[{}, {}]\n[]\n", conversion, conversion),
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = vec![];
+        let mut printer = Printer::new(Format::Json, None, &mut out, &mut err).with_quiet(true);
+        printer.print_all(&results).unwrap();
+        assert_eq!("[10000, 10000]\n", String::from_utf8(out).unwrap());
+
+        let mut out = vec![];
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err);
+        printer.print_all(&results).unwrap();
+        assert_eq!("10000.00m\n10000.00m\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn csv() {
+        let mut results = results();
+        results.append(&mut self::results());
+
+        let (out, err) = print(Format::Csv { header: true }, None, &results);
+        assert_eq!(
+            "from_quantity,from_unit,to_quantity,to_unit\n10,km,10000,m\n10,km,10000,m\n",
+            out
+        );
+        assert_eq!("invalid value\ninvalid value\n", err);
+
+        let (out, _) = print(Format::Csv { header: false }, Some(1), &results);
+        assert_eq!("10,km,10000.0,m\n10,km,10000.0,m\n", out);
+    }
+
+    #[test]
+    fn verbose() {
+        let (mut out, mut err) = (vec![], vec![]);
+        let mut printer = Printer::new(Format::Text, None, &mut out, &mut err).with_verbose(true);
+        results().iter().for_each(|r| printer.print(r).unwrap());
+        assert_eq!("10.00km = 10000.00m\n", String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,86 @@
+//! An interactive mode for doing many conversions in a row.
+//!
+//! Each line is read as `VALUE TARGET_UNIT` and the result is printed. Line
+//! editing and history aren't supported yet, but would be a nice improvement.
+use super::expr;
+use std::io::{self, BufRead, Write};
+
+/// The prompt printed before reading each line.
+const PROMPT: &str = "> ";
+
+/// Runs the REPL until EOF or `quit`/`exit` is entered.
+///
+/// Results are written to `out`, while the prompt and any errors are written to
+/// `err` so that the output can be piped elsewhere. When humanize is set, each
+/// result is converted into its most readable unit.
+pub fn run(
+    mut input: impl BufRead,
+    mut out: impl Write,
+    mut err: impl Write,
+    humanize: bool,
+) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        write!(err, "{}", PROMPT)?;
+        err.flush()?;
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        match line.trim() {
+            "" => continue,
+            "quit" | "exit" => return Ok(()),
+            expression => {
+                match expr::convert(expression).and_then(|res| expr::humanize(res, humanize)) {
+                    Ok(res) => writeln!(out, "{}", res.to)?,
+                    Err(e) => writeln!(err, "{}", e)?,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl(input: &str) -> (String, String) {
+        repl_with(input, false)
+    }
+
+    fn repl_with(input: &str, humanize: bool) -> (String, String) {
+        let (mut out, mut err) = (vec![], vec![]);
+        run(input.as_bytes(), &mut out, &mut err, humanize).unwrap();
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn converts_until_eof() {
+        let (out, err) = repl("10km m\n\n1m cm\n");
+        assert_eq!("10000.00m\n100.00cm\n", out);
+        assert_eq!("> > > > ", err);
+    }
+
+    #[test]
+    fn humanizes_results() {
+        assert_eq!("1.50km\n", repl_with("1500m m\n", true).0);
+    }
+
+    #[test]
+    fn stops_on_quit() {
+        assert_eq!("10000.00m\n", repl("10km m\nquit\n1m cm\n").0);
+        assert_eq!("10000.00m\n", repl("10km m\nexit\n1m cm\n").0);
+    }
+
+    #[test]
+    fn continues_after_errors() {
+        let (out, err) = repl("abc m\n10km m\n");
+        assert_eq!("10000.00m\n", out);
+        assert_eq!("> invalid value\n> > ", err);
+    }
+}
@@ -0,0 +1,69 @@
+//! ANSI styling for terminal output.
+
+/// Applies ANSI styles to text when enabled, otherwise returns it untouched.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Returns the value with a bold quantity and a cyan unit.
+    pub fn value(&self, v: &core::Value, precision: usize) -> String {
+        format!(
+            "{}{}",
+            self.bold(&format!("{:.*}", precision, v.quantity)),
+            self.cyan(&v.unit)
+        )
+    }
+
+    pub fn bold(&self, s: &str) -> String {
+        self.paint("1", s)
+    }
+
+    pub fn dim(&self, s: &str) -> String {
+        self.paint("2", s)
+    }
+
+    pub fn cyan(&self, s: &str) -> String {
+        self.paint("36", s)
+    }
+
+    pub fn red(&self, s: &str) -> String {
+        self.paint("31", s)
+    }
+
+    fn paint(&self, code: &str, s: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, s)
+        } else {
+            s.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled() {
+        let style = Style::new(false);
+        assert_eq!("212.00f", style.value(&core::Value::new(212.0, "f"), 2));
+        assert_eq!("oops", style.red("oops"));
+    }
+
+    #[test]
+    fn enabled() {
+        let style = Style::new(true);
+        assert_eq!(
+            "\x1b[1m212.0\x1b[0m\x1b[36mf\x1b[0m",
+            style.value(&core::Value::new(212.0, "f"), 1)
+        );
+        assert_eq!("\x1b[31moops\x1b[0m", style.red("oops"));
+        assert_eq!("\x1b[2mmeter\x1b[0m", style.dim("meter"));
+    }
+}
@@ -0,0 +1,203 @@
+//! A live mode that converts the expression as it's being typed.
+//!
+//! The terminal is put into raw mode so each key press can be handled as it
+//! arrives, and the line is redrawn with the latest result after every change.
+use super::expr;
+use std::io::{self, Read, Write};
+
+/// The result of handling a single key press.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Key {
+    /// The input changed and the line should be redrawn.
+    Changed,
+    /// The key was ignored (e.g. backspace on an empty line).
+    Ignored,
+    /// Enter or Ctrl+C was pressed.
+    Done,
+}
+
+/// Tracks the expression typed so far.
+#[derive(Debug, Default)]
+pub struct Input {
+    line: String,
+    pending: Vec<u8>,
+}
+
+impl Input {
+    /// Handles a byte read from the terminal. Multi-byte characters (e.g. `μ`)
+    /// are buffered until they're complete.
+    pub fn push(&mut self, byte: u8) -> Key {
+        match byte {
+            b'\r' | b'\n' | 0x03 | 0x04 => Key::Done,
+            0x7f | 0x08 => match self.line.pop() {
+                Some(_) => Key::Changed,
+                None => Key::Ignored,
+            },
+            // Other control characters (e.g. arrow keys) aren't supported.
+            b if b < 0x20 => Key::Ignored,
+            b => {
+                self.pending.push(b);
+                match std::str::from_utf8(&self.pending) {
+                    Ok(s) => {
+                        self.line.push_str(s);
+                        self.pending.clear();
+                        Key::Changed
+                    }
+                    Err(e) if e.error_len().is_some() => {
+                        self.pending.clear();
+                        Key::Ignored
+                    }
+                    Err(_) => Key::Ignored,
+                }
+            }
+        }
+    }
+
+    /// Returns the expression typed so far.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Returns the result of converting the current expression, or an empty
+    /// string when it isn't valid (yet).
+    pub fn result(&self) -> String {
+        expr::convert(&self.line)
+            .map(|res| res.to.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Returns the line to draw for the current state, which replaces whatever
+    /// was drawn before it.
+    pub fn render(&self) -> String {
+        match self.result() {
+            res if res.is_empty() => format!("\r\x1b[K{}", self.line()),
+            res => format!("\r\x1b[K{} = {}", self.line(), res),
+        }
+    }
+}
+
+/// Runs the watch mode until Enter or Ctrl+C is pressed, leaving the last
+/// result on the screen.
+pub fn run(mut input: impl Read, mut out: impl Write) -> io::Result<()> {
+    let _raw = RawMode::enable()?;
+    let mut state = Input::default();
+    let mut byte = [0u8; 1];
+
+    write!(out, "{}", state.render())?;
+    out.flush()?;
+
+    while input.read(&mut byte)? == 1 {
+        match state.push(byte[0]) {
+            Key::Changed => write!(out, "{}", state.render())?,
+            Key::Ignored => continue,
+            Key::Done => break,
+        }
+        out.flush()?;
+    }
+
+    write!(out, "\r\n")?;
+    out.flush()
+}
+
+/// Puts stdin into raw mode (no echo, line buffering or signals), restoring
+/// the original settings when dropped.
+struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        // SAFETY: termios is a plain C struct, so a zeroed one is valid to pass
+        // to tcgetattr, which fills it in.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // Nothing useful can be done if this fails, so the error is ignored.
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(s: &str) -> Input {
+        let mut input = Input::default();
+        s.bytes().for_each(|b| {
+            input.push(b);
+        });
+        input
+    }
+
+    #[test]
+    fn evaluates_as_typed() {
+        let mut input = Input::default();
+        for b in "10km ".bytes() {
+            assert_eq!(Key::Changed, input.push(b));
+            assert_eq!("", input.result());
+        }
+
+        assert_eq!(Key::Changed, input.push(b'm'));
+        assert_eq!("10km m", input.line());
+        assert_eq!("10000.00m", input.result());
+        assert_eq!("\r\x1b[K10km m = 10000.00m", input.render());
+    }
+
+    #[test]
+    fn invalid_input_is_blank() {
+        assert_eq!("", typed("abc").result());
+        assert_eq!("", typed("1xyz m").result());
+        assert_eq!("\r\x1b[K1xyz m", typed("1xyz m").render());
+        assert_eq!("\r\x1b[K", typed("").render());
+    }
+
+    #[test]
+    fn backspace() {
+        let mut input = typed("1km cmx");
+        assert_eq!(Key::Changed, input.push(0x7f));
+        assert_eq!("100000.00cm", input.result());
+
+        let mut input = Input::default();
+        assert_eq!(Key::Ignored, input.push(0x7f));
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let mut input = Input::default();
+        let bytes = "1 μv v".as_bytes();
+        assert_eq!(Key::Changed, input.push(bytes[0]));
+        assert_eq!(Key::Changed, input.push(bytes[1]));
+        assert_eq!(Key::Ignored, input.push(bytes[2]));
+        bytes[3..].iter().for_each(|&b| {
+            input.push(b);
+        });
+
+        assert_eq!("1 μv v", input.line());
+        assert_eq!(Key::Ignored, Input::default().push(0xff));
+    }
+
+    #[test]
+    fn exits_on_enter_or_ctrl_c() {
+        let mut input = typed("1m cm");
+        assert_eq!(Key::Done, input.push(b'\r'));
+        assert_eq!(Key::Done, input.push(b'\n'));
+        assert_eq!(Key::Done, input.push(0x03));
+        assert_eq!(Key::Ignored, input.push(0x1b));
+    }
+}
@@ -0,0 +1,469 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+fn conv(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_conv"))
+        .args(args)
+        .output()
+        .expect("failed to run conv")
+}
+
+fn conv_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_conv"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run conv");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8_lossy(&out.stdout).trim_end().to_string()
+}
+
+/// Parses CSV output (RFC 4180) into its records and fields.
+fn parse_csv(s: &str) -> Vec<Vec<String>> {
+    let (mut records, mut record, mut field) = (vec![], vec![], String::new());
+    let (mut chars, mut quoted) = (s.chars().peekable(), false);
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+
+    record.push(field);
+    records.push(record);
+    records
+}
+
+/// Splits a JSON array into its (top-level) elements, panicking if the output
+/// isn't a single array.
+fn parse_json_array(s: &str) -> Vec<String> {
+    let inner = s
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or_else(|| panic!("not a JSON array: {}", s));
+
+    let (mut elements, mut element) = (vec![], String::new());
+    let (mut depth, mut quoted, mut escaped) = (0, false, false);
+    for c in inner.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '{' | '[' if !quoted => depth += 1,
+            '}' | ']' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                elements.push(std::mem::take(&mut element).trim().to_string());
+                continue;
+            }
+            _ => {}
+        }
+        element.push(c);
+    }
+
+    assert_eq!((0, false), (depth, quoted), "unbalanced JSON array: {}", s);
+    if !element.trim().is_empty() {
+        elements.push(element.trim().to_string());
+    }
+    elements
+}
+
+#[test]
+fn json_conversion() {
+    let out = conv(&["--json", "10km", "m"]);
+    assert!(out.status.success());
+    assert_eq!(
+        r#"{"from": {"quantity": 10, "unit": "km"}, "to": {"quantity": 10000, "unit": "m"}}"#,
+        stdout(&out)
+    );
+}
+
+#[test]
+fn json_error() {
+    let out = conv(&["--json", "abc", "f"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!(r#"{"error": "invalid value"}"#, stdout(&out));
+
+    let out = conv(&["--json", "1xyz", "f"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!(r#"{"error": "unknown unit: xyz"}"#, stdout(&out));
+}
+
+#[test]
+fn json_units() {
+    let out = conv(&["--units", "--json"]);
+    assert!(out.status.success());
+
+    let out = stdout(&out);
+    assert!(out.starts_with("[{") && out.ends_with("}]"));
+    assert!(out.contains(
+        r#"{"id": "Temperature", "units": [{"symbol": "k", "names": ["kelvin", "kelvins"]}"#
+    ));
+    assert!(out.contains(r#"{"symbol": "nmi", "names": ["nautical mile", "nautical miles"]}"#));
+}
+
+#[test]
+fn csv_conversion() {
+    let out = conv(&["--csv", "10km", "m"]);
+    assert!(out.status.success());
+    assert_eq!(
+        "from_quantity,from_unit,to_quantity,to_unit\n10,km,10000,m",
+        stdout(&out)
+    );
+
+    let out = conv(&["--csv", "--no-header", "10km", "m"]);
+    assert!(out.status.success());
+    assert_eq!("10,km,10000,m", stdout(&out));
+
+    let out = conv(&["--csv", "-p", "1", "24in", "ft"]);
+    assert_eq!(
+        vec![
+            vec!["from_quantity", "from_unit", "to_quantity", "to_unit"],
+            vec!["24", "in", "2.0", "ft"],
+        ],
+        parse_csv(&stdout(&out))
+    );
+}
+
+#[test]
+fn csv_error() {
+    let out = conv(&["--csv", "abc", "f"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("", stdout(&out));
+    assert_eq!("invalid value\n", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn repl() {
+    let out = conv_with_input(&["--repl"], "10km m\nabc m\n1m cm\nquit\n1m mm\n");
+    assert!(out.status.success());
+    assert_eq!("10000.00m\n100.00cm", stdout(&out));
+    assert_eq!(
+        "> > invalid value\n> > ",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn stdin() {
+    let out = conv_with_input(&[], "10km m\n\n1m cm\n");
+    assert!(out.status.success());
+    assert_eq!("10000.00m\n100.00cm", stdout(&out));
+
+    let out = conv_with_input(&[], "10km m\nabc m\n1m cm\n");
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("10000.00m\n100.00cm", stdout(&out));
+    assert_eq!(
+        "line 2: invalid value\n",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn stdin_csv() {
+    let out = conv_with_input(&["--csv"], "10km m\n1m cm\n");
+    assert!(out.status.success());
+    assert_eq!(
+        "from_quantity,from_unit,to_quantity,to_unit\n10,km,10000,m\n1,m,100,cm",
+        stdout(&out)
+    );
+
+    let out = conv_with_input(&["--csv", "-p", "1"], "3ft in\n36in ft\n");
+    assert!(out.status.success());
+    assert_eq!(
+        vec![
+            vec!["from_quantity", "from_unit", "to_quantity", "to_unit"],
+            vec!["3", "ft", "36.0", "in"],
+            vec!["36", "in", "3.0", "ft"],
+        ],
+        parse_csv(&stdout(&out))
+    );
+}
+
+#[test]
+fn file() {
+    let path: PathBuf = std::env::temp_dir().join(format!("conv-file-{}.txt", std::process::id()));
+    fs::write(&path, "# lengths\n10km m\n\nabc m\n1m cm\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    let out = conv(&["--file", path]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("10000.00m\n100.00cm", stdout(&out));
+    assert_eq!(
+        "line 4: invalid value\n",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let out = conv(&["--file", path, "--fail-fast"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("10000.00m", stdout(&out));
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn completions() {
+    for shell in ["bash", "zsh", "fish", "elvish", "powershell"] {
+        let out = conv(&["--completions", shell]);
+        assert!(out.status.success(), "{}", shell);
+        assert!(stdout(&out).contains("units"), "{}", shell);
+    }
+
+    assert!(!conv(&["--completions", "tcsh"]).status.success());
+}
+
+#[test]
+fn precision() {
+    assert_eq!("212f", stdout(&conv(&["--precision", "0", "100c", "f"])));
+    assert_eq!("0.0010km", stdout(&conv(&["--precision", "4", "1m", "km"])));
+    assert_eq!(
+        r#"{"from": {"quantity": 1, "unit": "m"}, "to": {"quantity": 0.0, "unit": "km"}}"#,
+        stdout(&conv(&["--json", "-p", "1", "1m", "km"]))
+    );
+}
+
+#[test]
+fn units_family() {
+    let out = conv(&["--units", "--family", "temperature"]);
+    assert!(out.status.success());
+    assert_eq!(
+        "Available units\n\n**Temperature:**\n\
+         _metric_\n\
+         k - kelvin (absolute thermodynamic temperature scale)\n\
+         c - celsius (water freezes at 0 and boils at 100)\n\
+         _imperial_\n\
+         f - fahrenheit (water freezes at 32 and boils at 212)\n\
+         r - rankine (absolute scale using fahrenheit degrees)",
+        stdout(&out)
+    );
+
+    let out = conv(&["--units", "--family", "widgets"]);
+    assert!(out.status.success());
+    assert!(stdout(&out).contains("**Lengths:**"));
+    assert!(stdout(&out).contains("**Temperature:**"));
+    assert_eq!(
+        "warning: unknown family: widgets\n",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    assert!(!conv(&["--family", "temperature", "1m", "km"])
+        .status
+        .success());
+}
+
+#[test]
+fn color() {
+    let out = stdout(&conv(&["--no-color", "10km", "m"]));
+    assert_eq!("10000.00m", out);
+
+    let out = stdout(&conv(&["--color", "10km", "m"]));
+    assert_eq!("\x1b[1m10000.00\x1b[0m\x1b[36mm\x1b[0m", out);
+
+    let out = stdout(&conv(&["--color", "--no-color", "10km", "m"]));
+    assert!(!out.contains('\x1b'));
+
+    let out = stdout(&conv(&["--units", "--color"]));
+    assert!(out.contains("\x1b[1mkm\x1b[0m - \x1b[2mkilometer (one thousand meters)\x1b[0m"));
+}
+
+#[test]
+fn quiet() {
+    assert_eq!("212.00", stdout(&conv(&["-q", "100c", "f"])));
+    assert_eq!("212", stdout(&conv(&["-q", "-p", "0", "100c", "f"])));
+    assert_eq!("10000", stdout(&conv(&["--json", "--quiet", "10km", "m"])));
+    assert!(!conv(&["--csv", "--quiet", "10km", "m"]).status.success());
+}
+
+#[test]
+fn families() {
+    let out = conv(&["--families"]);
+    assert!(out.status.success());
+
+    let out = stdout(&out);
+    let ids: Vec<&str> = out.lines().collect();
+    assert_eq!(vec!["Lengths", "Temperature", "Voltage"], ids);
+
+    let out = stdout(&conv(&["--families", "--json"]));
+    assert!(out.starts_with('[') && out.ends_with(']'));
+    assert!(out.contains(r#""Lengths""#));
+    assert!(out.contains(r#""Temperature""#));
+}
+
+#[test]
+fn exit_codes() {
+    let out = conv(&["100c", "f"]);
+    assert_eq!(Some(0), out.status.code());
+    assert_eq!("212.00f", stdout(&out));
+
+    let out = conv(&["abc", "f"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("", stdout(&out));
+    assert_eq!("invalid value\n", String::from_utf8_lossy(&out.stderr));
+
+    let out = conv(&["1xyz", "f"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("unknown unit: xyz\n", String::from_utf8_lossy(&out.stderr));
+
+    let out = conv(&["1km", "c"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!(
+        "can't convert km to c: they measure different things\n",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn subcommand_help() {
+    for cmd in ["convert", "list", "all", "check", "info", "cheatsheet"] {
+        let out = conv(&[cmd, "--help"]);
+        assert!(out.status.success(), "{}", cmd);
+        assert!(
+            stdout(&out).contains(&format!("Usage: conv {}", cmd)),
+            "{}",
+            cmd
+        );
+    }
+}
+
+#[test]
+fn convert_command() {
+    assert_eq!("212.00f", stdout(&conv(&["convert", "100c", "f"])));
+    assert_eq!(
+        "10.00km = 10000.00m",
+        stdout(&conv(&["convert", "--verbose", "10km", "m"]))
+    );
+    assert_eq!("212f", stdout(&conv(&["convert", "100c", "f", "-p", "0"])));
+    assert_eq!(
+        r#"{"from": {"quantity": 10, "unit": "km"}, "to": {"quantity": 10000, "unit": "m"}}"#,
+        stdout(&conv(&["convert", "10km", "m", "--json"]))
+    );
+    assert_eq!(Some(1), conv(&["convert", "abc", "m"]).status.code());
+}
+
+#[test]
+fn list_command() {
+    assert_eq!(
+        stdout(&conv(&["--units", "--family", "temperature"])),
+        stdout(&conv(&["list", "--family", "temperature"]))
+    );
+    assert!(stdout(&conv(&["list", "--json"])).starts_with("[{"));
+}
+
+#[test]
+fn all_command() {
+    assert_eq!("373.15k\n212.00f\n671.67r", stdout(&conv(&["all", "100c"])));
+    assert_eq!(
+        "from_quantity,from_unit,to_quantity,to_unit\n100,c,373.15,k\n100,c,212.00,f\n100,c,671.67,r",
+        stdout(&conv(&["all", "100c", "--csv", "-p", "2"]))
+    );
+    assert_eq!(Some(1), conv(&["all", "1xyz"]).status.code());
+
+    let out = stdout(&conv(&["all", "100c", "--json", "-p", "2"]));
+    assert_eq!(1, out.lines().count());
+    assert_eq!(
+        vec![
+            r#"{"from": {"quantity": 100, "unit": "c"}, "to": {"quantity": 373.15, "unit": "k"}}"#,
+            r#"{"from": {"quantity": 100, "unit": "c"}, "to": {"quantity": 212.00, "unit": "f"}}"#,
+            r#"{"from": {"quantity": 100, "unit": "c"}, "to": {"quantity": 671.67, "unit": "r"}}"#,
+        ],
+        parse_json_array(&out)
+    );
+}
+
+#[test]
+fn check_command() {
+    let out = conv(&["check", "KM"]);
+    assert!(out.status.success());
+    assert_eq!("km - kilometer (Lengths)", stdout(&out));
+
+    let out = conv(&["check", "xyz"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!("unknown unit: xyz\n", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn info_command() {
+    let out = conv(&["info", "km"]);
+    assert!(out.status.success());
+    assert_eq!(
+        vec![
+            "km (kilometer)",
+            "  family:      Lengths",
+            "  aliases:     kilometer, kilometers",
+            "  description: one thousand meters",
+            "  ratio:       1000",
+            "  difference:  0",
+            "  to base:     1 km = 1000 m",
+            "  from base:   1 m = 0.001 km",
+        ],
+        stdout(&out).lines().collect::<Vec<_>>()
+    );
+
+    let out = conv(&["info", "xyz"]);
+    assert_eq!(Some(1), out.status.code());
+    assert_eq!(
+        "unit not found: xyz\n",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let out = conv(&["info", "kilometre"]);
+    assert_eq!(Some(1), out.status.code());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.starts_with("unit not found: kilometre\ndid you mean: km (kilometer)"));
+}
+
+#[test]
+fn cheatsheet() {
+    let out = conv(&["cheatsheet", "km"]);
+    assert!(out.status.success());
+
+    let out = stdout(&out);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(9, lines.len());
+    assert_eq!("1.0000km =", lines[0]);
+    assert_eq!("  1000000.0000mm", lines[1]);
+    assert!(lines.contains(&"  1000.0000m"));
+
+    assert!(stdout(&conv(&["cheatsheet", "c", "100"])).contains("  212.0000f"));
+    assert!(stdout(&conv(&["cheatsheet", "c", "-40"])).contains("  -40.0000f"));
+    assert_eq!(Some(1), conv(&["cheatsheet", "xyz"]).status.code());
+}
+
+#[test]
+fn humanize() {
+    assert_eq!("1.50km", stdout(&conv(&["--humanize", "1500000mm", "m"])));
+    assert_eq!("1.00mm", stdout(&conv(&["0.1cm", "m", "--humanize"])));
+    assert_eq!(
+        "1500.00m = 1.50km",
+        stdout(&conv(&["convert", "-v", "--humanize", "1500m", "m"]))
+    );
+    assert!(!conv(&["--humanize", "1xyz", "m"]).status.success());
+
+    // Humanize applies to every mode that converts, not just single values.
+    let out = conv_with_input(&["--humanize"], "1500m m\n");
+    assert_eq!("1.50km", stdout(&out));
+    let out = conv_with_input(&["--repl", "--humanize"], "1500m m\n");
+    assert_eq!("1.50km", stdout(&out));
+}
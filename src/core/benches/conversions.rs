@@ -0,0 +1,102 @@
+//! Conversion throughput benchmarks.
+//!
+//! criterion isn't a dependency of this crate, so this is a small hand-rolled
+//! harness: each benchmark runs its closure for a fixed number of iterations
+//! (after a warm up) and reports the mean time per iteration. Run it with:
+//!
+//!     cargo bench -p core
+use core::{Family, Unit, UnitRegistry, Value};
+use std::hint::black_box;
+use std::time::Instant;
+
+const WARMUP: u32 = 10_000;
+const ITERATIONS: u32 = 200_000;
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    for _ in 0..WARMUP {
+        black_box(f());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+
+    let per_iter = start.elapsed().as_nanos() / u128::from(ITERATIONS);
+    println!("{:<40} {:>8} ns/iter", name, per_iter);
+}
+
+/// The lookup used before units were indexed, kept as a baseline.
+fn linear_find<'a>(family: &'a Family, unit: &str) -> Option<&'a Unit> {
+    let unit = unit.to_lowercase();
+    family
+        .units()
+        .iter()
+        .find(|u| u.names.contains(&unit) || u.symbol == unit)
+}
+
+fn family(unit: &str) -> &'static Family {
+    UnitRegistry::global().find(unit).unwrap()
+}
+
+/// A family with the given number of units (each with two names), built here so
+/// the gap between the scan and the index doesn't depend on the sizes of the
+/// built-in families.
+fn synthetic_family(len: usize) -> Family {
+    let units = (1..=len)
+        .map(|i| {
+            let (one, many) = (format!("widget{}", i), format!("widgets{}", i));
+            Unit::new(vec![&one, &many], &format!("w{}", i), i as f64, 0.0)
+        })
+        .collect();
+
+    Family::new("Synthetic", "w1", units)
+}
+
+fn main() {
+    let start = Instant::now();
+
+    // temperature is a short family (4 units), lengths a long one.
+    for (label, from, to) in [("temperature", "c", "f"), ("lengths", "nmi", "cm")] {
+        bench(&format!("convert/{}", label), || {
+            core::convert(Value::new(100.0, from), to).unwrap()
+        });
+
+        bench(&format!("convert_all/{}", label), || {
+            core::convert_all(Value::new(100.0, from)).unwrap()
+        });
+
+        // The last unit (and name) in a family is the worst case for a scan.
+        let fam = family(from);
+        let last = fam.units().last().unwrap().names.last().unwrap().clone();
+        bench(&format!("find_unit/{}/baseline", label), || {
+            linear_find(fam, &last)
+        });
+        bench(&format!("find_unit/{}/index", label), || {
+            fam.unit_by_symbol(&last)
+        });
+
+        bench(&format!("find_family/{}", label), || {
+            UnitRegistry::global().find(&last)
+        });
+    }
+
+    for len in [25, 100] {
+        let fam = synthetic_family(len);
+        let last = fam.units().last().unwrap().names.last().unwrap().clone();
+        bench(&format!("find_unit/synthetic{}/baseline", len), || {
+            linear_find(&fam, &last)
+        });
+        bench(&format!("find_unit/synthetic{}/index", len), || {
+            fam.unit_by_symbol(&last)
+        });
+    }
+
+    for input in ["100c", "-12.5 km", "3 nmi"] {
+        bench(&format!("parse/{:?}", input), || {
+            input.parse::<Value>().unwrap()
+        });
+    }
+
+    println!("\ntotal: {:?}", start.elapsed());
+}
@@ -0,0 +1,132 @@
+use super::measurement::Measurement;
+use super::prelude::{ConversionError, Value};
+use super::registry::UnitRegistry;
+use std::result;
+
+/// Converts each of the values into the given unit, returning the results in
+/// the same order. The measurement for the unit is only looked up once, so this
+/// is cheaper than calling `convert` in a loop.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let values = vec![core::Value::new(1.0, "km"), core::Value::new(1.0, "c")];
+/// let res = core::batch_convert(values, "m");
+/// assert_eq!(Ok(core::Value::new(1000.0, "m")), res[0]);
+/// assert!(res[1].is_err());
+/// # }
+/// ```
+pub fn batch_convert(
+    values: Vec<Value>,
+    to_unit: &str,
+) -> Vec<result::Result<Value, ConversionError>> {
+    let measurement = UnitRegistry::global().find_measurement(to_unit);
+    convert_each(measurement, values, to_unit)
+}
+
+/// Like `batch_convert`, but splits the values across a thread per available
+/// CPU. This is only worth it for large batches.
+#[cfg(feature = "parallel")]
+pub fn batch_convert_par(
+    values: Vec<Value>,
+    to_unit: &str,
+) -> Vec<result::Result<Value, ConversionError>> {
+    let measurement = UnitRegistry::global().find_measurement(to_unit);
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let size = values.len().div_ceil(threads).max(1);
+
+    let mut chunks = vec![];
+    let mut rest = values;
+    while rest.len() > size {
+        let tail = rest.split_off(size);
+        chunks.push(rest);
+        rest = tail;
+    }
+    chunks.push(rest);
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| s.spawn(move || convert_each(measurement, chunk, to_unit)))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("conversion thread panicked"))
+            .collect()
+    })
+}
+
+fn convert_each(
+    measurement: Option<&dyn Measurement>,
+    values: Vec<Value>,
+    to_unit: &str,
+) -> Vec<result::Result<Value, ConversionError>> {
+    values
+        .into_iter()
+        .map(|v| match measurement {
+            Some(m) => m.convert(v, to_unit),
+            None => Err(UnitRegistry::global().unknown_unit(to_unit)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Vec<Value> {
+        vec![
+            Value::new(1.0, "km"),
+            Value::new(12.0, "in"),
+            Value::new(1.0, "xyz"),
+            Value::new(-3.5, "mi"),
+            Value::new(100.0, "c"),
+        ]
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn matches_convert() {
+        let res = batch_convert(values(), "m");
+        assert_eq!(5, res.len());
+
+        // The errors can differ, since the measurement is found by the target
+        // unit rather than the value's unit.
+        for (v, got) in values().into_iter().zip(res) {
+            match crate::convert_paired(v, "m") {
+                Ok(want) => assert_eq!(Ok(want.to), got),
+                Err(_) => assert!(got.is_err()),
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_unit() {
+        let res = batch_convert(values(), "abc");
+        assert!(res
+            .iter()
+            .all(|r| r == &Err(ConversionError::unknown_unit("abc"))));
+        assert!(batch_convert(vec![], "m").is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "parallel", feature = "length", feature = "temperature"))]
+    fn parallel_matches_serial() {
+        let many: Vec<Value> = values().into_iter().cycle().take(1001).collect();
+        let res = batch_convert_par(many.clone(), "ft");
+
+        assert_eq!(batch_convert(many, "ft"), res);
+        assert_eq!(400, res.iter().filter(|r| r.is_err()).count());
+        assert_eq!(
+            Value::new(3280.84, "ft"),
+            res[1000]
+                .clone()
+                .unwrap()
+                .map(|q| (q * 100.0).round() / 100.0)
+        );
+        assert!(batch_convert_par(vec![], "ft").is_empty());
+        assert_eq!(1, batch_convert_par(values()[..1].to_vec(), "m").len());
+    }
+}
@@ -0,0 +1,182 @@
+use super::prelude::{Family, FamilyValidationError, PhysicalConstraint, RangeBehavior, Unit};
+use std::result;
+
+/// Builds a Family, validating it before it's handed out.
+///
+/// # Examples
+///
+/// ```
+/// use core::{FamilyBuilder, Unit};
+///
+/// let family = FamilyBuilder::new("Widgets", "w")
+///     .unit(Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0))
+///     .unit(Unit::new(vec!["kilowidget", "kilowidgets"], "kw", 1000.0, 0.0))
+///     .build()
+///     .unwrap();
+///
+/// assert!(family.can_convert("kilowidgets"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FamilyBuilder {
+    id: String,
+    base_unit: String,
+    units: Vec<Unit>,
+    description: Option<String>,
+    constraint: Option<PhysicalConstraint>,
+    max_quantity: Option<f64>,
+    range: Option<(Option<f64>, Option<f64>, RangeBehavior)>,
+    metric: bool,
+}
+
+impl FamilyBuilder {
+    /// Constructs a new, empty builder for the family.
+    pub fn new(id: &str, base_unit: &str) -> Self {
+        Self {
+            id: id.into(),
+            base_unit: base_unit.into(),
+            units: vec![],
+            description: None,
+            constraint: None,
+            max_quantity: None,
+            range: None,
+            metric: false,
+        }
+    }
+
+    /// Adds a unit to the family.
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.units.push(unit);
+        self
+    }
+
+    /// Adds all of the units to the family.
+    pub fn units<I: IntoIterator<Item = Unit>>(mut self, units: I) -> Self {
+        self.units.extend(units);
+        self
+    }
+
+    /// Sets the human readable description of the family.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the physical constraint for the family.
+    pub fn constraint(mut self, constraint: PhysicalConstraint) -> Self {
+        self.constraint = Some(constraint);
+        self
+    }
+
+    /// Sets the largest (absolute) quantity a conversion may produce.
+    pub fn max_quantity(mut self, max: f64) -> Self {
+        self.max_quantity = Some(max);
+        self
+    }
+
+    /// Marks the family as metric (see `Family::with_metric`).
+    pub fn metric(mut self, metric: bool) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Sets the natural range of the family's values (see `Family::with_range`).
+    pub fn range(mut self, min: Option<f64>, max: Option<f64>, behavior: RangeBehavior) -> Self {
+        self.range = Some((min, max, behavior));
+        self
+    }
+
+    /// Builds the family, returning every problem found by `Family::validate`.
+    pub fn build(self) -> result::Result<Family, Vec<FamilyValidationError>> {
+        let mut family = Family::new_unchecked(&self.id, &self.base_unit, self.units);
+        family.description = self.description;
+        family.constraint = self.constraint;
+        family.max_quantity = self.max_quantity;
+        family.metric = self.metric;
+        if let Some((min, max, behavior)) = self.range {
+            family = family.with_range(min, max, behavior);
+        }
+        family.validate().map(|_| family)
+    }
+}
+
+/// Implements Extend for FamilyBuilder, adding all of the units to the family
+/// (see `FamilyBuilder::units`).
+impl Extend<Unit> for FamilyBuilder {
+    fn extend<I: IntoIterator<Item = Unit>>(&mut self, units: I) {
+        self.units.extend(units);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let fam = FamilyBuilder::new("Widgets", "w")
+            .unit(Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0))
+            .units(vec![Unit::new(vec!["kilowidget"], "kw", 1000.0, 0.0)])
+            .constraint(PhysicalConstraint::NonNegative)
+            .max_quantity(1e12)
+            .range(Some(0.0), None, RangeBehavior::Clamp)
+            .description("things")
+            .metric(true)
+            .build()
+            .unwrap();
+
+        assert_eq!("Widgets", fam.id);
+        assert_eq!(2, fam.units().len());
+        assert!(fam.can_convert("kilowidget"));
+        assert_eq!(Some(PhysicalConstraint::NonNegative), fam.constraint);
+        assert_eq!(Some(1e12), fam.max_quantity);
+        assert!(fam.metric);
+        assert_eq!((Some(0.0), None), (fam.min, fam.max));
+        assert_eq!(Some("things".into()), fam.description);
+    }
+
+    #[test]
+    fn extend() {
+        let mut builder = FamilyBuilder::new("Widgets", "w");
+        builder.extend(vec![
+            Unit::new(vec!["widget"], "w", 1.0, 0.0),
+            Unit::new(vec!["kilowidget"], "kw", 1000.0, 0.0),
+        ]);
+
+        let fam = builder.build().unwrap();
+        assert_eq!(2, fam.units().len());
+        assert!(fam.can_convert("kilowidget"));
+    }
+
+    #[test]
+    fn build_invalid() {
+        assert_eq!(
+            Err(vec![FamilyValidationError::BaseUnitNotFound]),
+            FamilyBuilder::new("Widgets", "w").build()
+        );
+
+        assert_eq!(
+            Err(vec![FamilyValidationError::DuplicateSymbol("w".into())]),
+            FamilyBuilder::new("Widgets", "w")
+                .unit(Unit::new(vec!["widget"], "w", 1.0, 0.0))
+                .unit(Unit::new(vec!["wodget"], "w", 2.0, 0.0))
+                .build()
+        );
+
+        let meter = || Unit::new(vec!["meter"], "m", 1.0, 0.0);
+        assert_eq!(
+            Err(vec![FamilyValidationError::EmptyId]),
+            FamilyBuilder::new("", "m").unit(meter()).build()
+        );
+        assert_eq!(
+            Err(vec![FamilyValidationError::EmptyBaseUnit]),
+            FamilyBuilder::new("Lengths", "").unit(meter()).build()
+        );
+        assert_eq!(
+            Err(vec![
+                FamilyValidationError::InvalidId("Len\ngths".into()),
+                FamilyValidationError::EmptyBaseUnit,
+            ]),
+            FamilyBuilder::new("Len\ngths", "").unit(meter()).build()
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use super::measurement::Measurement;
+use super::prelude::{ConversionError, Family, FamilyValidationError, Unit, Value};
+use std::collections::HashMap;
+use std::result;
+
+/// The currency all exchange rates are relative to.
+const BASE_CURRENCY: &str = "USD";
+
+/// A measurement that converts between currencies using exchange rates supplied
+/// at startup (e.g. fetched from a rates API).
+///
+/// Each rate is the amount of the currency that one US dollar buys (e.g. `0.92`
+/// for EUR), so USD is always available with a rate of 1. The rates are fixed
+/// once the family has been created; registering new rates requires a new
+/// CurrencyFamily with a different id (see `CurrencyFamily::with_id`).
+///
+/// # Examples
+///
+/// ```
+/// use core::{CurrencyFamily, Measurement, Value};
+/// use std::collections::HashMap;
+///
+/// let currencies = CurrencyFamily::new(HashMap::from([("EUR".to_string(), 0.5)])).unwrap();
+/// let v = currencies.convert(Value::new(10.0, "eur"), "usd").unwrap();
+/// assert_eq!(Value::new(20.0, "usd"), v);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CurrencyFamily {
+    family: Family,
+}
+
+impl CurrencyFamily {
+    /// Creates a new CurrencyFamily from the given exchange rates, keyed by
+    /// currency code (e.g. `EUR`) and relative to USD.
+    ///
+    /// Returns `FamilyValidationError::InvalidRatio` for every rate that isn't a
+    /// positive, finite number, along with any other problem found by
+    /// `Family::validate`.
+    pub fn new(
+        exchange_rates: HashMap<String, f64>,
+    ) -> result::Result<Self, Vec<FamilyValidationError>> {
+        let mut codes: Vec<_> = exchange_rates
+            .keys()
+            .filter(|code| !code.eq_ignore_ascii_case(BASE_CURRENCY))
+            .collect();
+        codes.sort();
+
+        // Units can't be created with NaN ratios, so the rates are checked first.
+        let invalid: Vec<_> = codes
+            .iter()
+            .filter(|code| !(exchange_rates[**code].is_finite() && exchange_rates[**code] > 0.0))
+            .map(|code| FamilyValidationError::InvalidRatio(code.to_lowercase()))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(invalid);
+        }
+
+        let mut units = vec![Unit::new(vec![BASE_CURRENCY], BASE_CURRENCY, 1.0, 0.0)];
+        units.extend(
+            codes
+                .into_iter()
+                .map(|code| Unit::new(vec![code], code, 1.0 / exchange_rates[code], 0.0)),
+        );
+
+        let family = Family::new("Currencies", BASE_CURRENCY, units)
+            .with_description("currencies at fixed exchange rates");
+        family.validate().map(|_| Self { family })
+    }
+
+    /// Sets the id of the family (`Currencies` by default), so that families with
+    /// different rates can be registered side by side.
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.family.id = id.into();
+        self
+    }
+}
+
+impl Measurement for CurrencyFamily {
+    fn id(&self) -> &str {
+        &self.family.id
+    }
+
+    fn can_convert(&self, unit: &str) -> bool {
+        self.family.can_convert(unit)
+    }
+
+    fn convert(&self, v: Value, to_unit: &str) -> result::Result<Value, ConversionError> {
+        self.family.convert(v, to_unit)
+    }
+
+    fn family(&self) -> Option<&Family> {
+        Some(&self.family)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::assertions::*;
+
+    fn currencies() -> CurrencyFamily {
+        CurrencyFamily::new(HashMap::from([
+            ("EUR".to_string(), 0.92),
+            ("GBP".to_string(), 0.79),
+            ("JPY".to_string(), 150.0),
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn invariants() {
+        let currencies = currencies();
+        let fam = currencies.family().unwrap();
+
+        assert_family_invariants(fam);
+        assert_eq!("USD", fam.base_unit);
+        assert_eq!(
+            vec!["usd", "eur", "gbp", "jpy"],
+            fam.units()
+                .iter()
+                .map(|u| u.symbol.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let fam = currencies();
+        let convert = |qty, from, to: &str| fam.convert(Value::new(qty, from), to).unwrap();
+        let cases = [
+            (100.0, "usd", "eur", 92.0),
+            (92.0, "eur", "usd", 100.0),
+            (100.0, "usd", "jpy", 15000.0),
+            (100.0, "eur", "gbp", 85.869),
+            (100.0, "jpy", "gbp", 0.527),
+        ];
+
+        for (qty, from, to, exp) in cases {
+            assert_quantity_in_delta(exp, convert(qty, from, to).quantity, DEFAULT_EPSILON);
+        }
+
+        assert_eq!(Value::new(5.0, "eur"), convert(5.0, "eur", "eur"));
+    }
+
+    #[test]
+    fn symmetry() {
+        assert_full_symmetry(currencies().family().unwrap(), DEFAULT_EPSILON);
+    }
+
+    #[test]
+    fn base_currency_rate_is_ignored() {
+        let fam = CurrencyFamily::new(HashMap::from([
+            ("usd".to_string(), 2.0),
+            ("EUR".to_string(), 0.5),
+        ]))
+        .unwrap();
+
+        assert_eq!(2, fam.family().unwrap().units().len());
+        assert_eq!(
+            Value::new(2.0, "usd"),
+            fam.convert(Value::new(1.0, "eur"), "usd").unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_currency() {
+        assert!(!currencies().can_convert("chf"));
+        assert!(currencies().convert(Value::new(1.0, "usd"), "chf").is_err());
+    }
+
+    #[test]
+    fn invalid_rate() {
+        for rate in [0.0, -1.0, f64::NAN] {
+            let err = CurrencyFamily::new(HashMap::from([("EUR".to_string(), rate)])).unwrap_err();
+            assert_eq!(vec![FamilyValidationError::InvalidRatio("eur".into())], err);
+        }
+    }
+
+    #[test]
+    fn with_id() {
+        let fam = currencies().with_id("Currencies (2024)");
+        assert_eq!("Currencies (2024)", fam.id());
+        assert_family_invariants(fam.family().unwrap());
+    }
+}
@@ -0,0 +1,93 @@
+use super::prelude::{ParseValueError, Value};
+use std::result;
+
+/// Parses an angle in degrees, minutes and seconds (e.g. `40°30'15"N`) into a
+/// Value in decimal degrees (`deg`).
+///
+/// The parts may be marked with symbols (`40°30'15"`), letters (`40d30m15s`) or
+/// just separated by spaces (`40 30 15`), and minutes and seconds are optional.
+/// A trailing hemisphere of `S` or `W` negates the angle.
+///
+/// # Examples
+///
+/// ```
+/// let v = core::parse_dms("40°30'0\"N").unwrap();
+/// assert_eq!(core::Value::new(40.5, "deg"), v);
+///
+/// let v = core::parse_dms("45d 0m 0s W").unwrap();
+/// assert_eq!(core::Value::new(-45.0, "deg"), v);
+/// ```
+pub fn parse_dms(s: &str) -> result::Result<Value, ParseValueError> {
+    lazy_static! {
+        static ref RE: regex::Regex = regex::Regex::new(concat!(
+            r"^(-)?(\d+(?:\.\d+)?)\s*(?:[°d]|\s|$)\s*",
+            r#"(?:(\d+(?:\.\d+)?)\s*(?:['′m]|\s|$)\s*)?"#,
+            r#"(?:(\d+(?:\.\d+)?)\s*["″s]?\s*)?"#,
+            r"([NSEW])?$",
+        ))
+        .unwrap();
+    }
+
+    let cap = RE
+        .captures(s.trim())
+        .ok_or_else(|| ParseValueError::new("invalid angle"))?;
+
+    let part = |i| -> result::Result<f64, ParseValueError> {
+        Ok(match cap.get(i) {
+            Some(m) => m.as_str().parse::<f64>()?,
+            None => 0.0,
+        })
+    };
+
+    let (degrees, minutes, seconds) = (part(2)?, part(3)?, part(4)?);
+    if minutes >= 60.0 || seconds >= 60.0 {
+        return Err(ParseValueError::new(
+            "minutes and seconds must be less than 60",
+        ));
+    }
+
+    let angle = degrees + minutes / 60.0 + seconds / 3600.0;
+    let southwest = matches!(cap.get(5).map(|m| m.as_str()), Some("S" | "W"));
+
+    Ok(match cap.get(1).is_some() ^ southwest {
+        true => Value::new(-angle, "deg"),
+        false => Value::new(angle, "deg"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::assertions::*;
+
+    #[test]
+    fn formats() {
+        let cases = [
+            ("40°30'0\"", 40.5),
+            ("0°30'30\"", 0.508_333),
+            ("45°0'0\"S", -45.0),
+            ("40°30'15\"N", 40.504_167),
+            ("40d30m15s", 40.504_167),
+            ("40 30 15", 40.504_167),
+            ("40 30 15 W", -40.504_167),
+            ("12° 30′ 36″ E", 12.51),
+            ("-12°30'", -12.5),
+            ("90°", 90.0),
+            ("45", 45.0),
+            ("  10.5d  ", 10.5),
+        ];
+
+        for (given, want) in cases {
+            let v = parse_dms(given).unwrap_or_else(|e| panic!("{}: {}", given, e));
+            assert_eq!("deg", v.unit, "{}", given);
+            assert_quantity_in_delta(want, v.quantity, 1e-6);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for given in ["", "abc", "40°61'", "40°30'75\"", "40°30'15\"X", "N"] {
+            assert!(parse_dms(given).is_err(), "{}", given);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::prelude::{Result, Value};
+
+/// Extends iterators of values with conversion adaptors.
+///
+/// # Examples
+///
+/// ```
+/// use core::{Value, ValueIteratorExt};
+///
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// let readings = vec![Value::new(0.0, "c"), Value::new(32.0, "f")];
+/// let kelvins = readings
+///     .into_iter()
+///     .converted_to("k")
+///     .collect::<anyhow::Result<Vec<_>>>()?;
+///
+/// println!("{:?}", kelvins);
+/// // [Value { quantity: 273.15, unit: "k" }, Value { quantity: 273.15, unit: "k" }]
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+pub trait ValueIteratorExt: Iterator<Item = Value> + Sized {
+    /// Returns an iterator that converts each value into the given unit.
+    ///
+    /// Values that can't be converted yield an `Err` without ending the iteration.
+    fn converted_to(self, unit: &str) -> ConvertedTo<Self> {
+        ConvertedTo {
+            iter: self,
+            unit: unit.into(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Value>> ValueIteratorExt for I {}
+
+/// An iterator that converts values into a single unit.
+///
+/// This is created by `ValueIteratorExt::converted_to`.
+#[derive(Debug, Clone)]
+pub struct ConvertedTo<I> {
+    iter: I,
+    unit: String,
+}
+
+impl<I: Iterator<Item = Value>> Iterator for ConvertedTo<I> {
+    type Item = Result;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|v| crate::convert(v, &self.unit))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "length", feature = "temperature"))]
+mod tests {
+    use super::*;
+    use crate::testutil::assertions::*;
+
+    #[test]
+    fn converted_to() -> Result {
+        let readings = vec![
+            Value::new(100.0, "c"),
+            Value::new(212.0, "f"),
+            Value::new(373.15, "k"),
+        ];
+
+        let all = readings
+            .into_iter()
+            .converted_to("celsius")
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        assert_eq!(3, all.len());
+        all.iter().try_for_each(|v| {
+            assert_in_delta(&Value::new(100.0, "celsius"), v, DEFAULT_EPSILON).map(|_| ())
+        })?;
+
+        Value::ok()
+    }
+
+    #[test]
+    fn converted_to_errors() {
+        let readings = vec![
+            Value::new(100.0, "c"),
+            Value::new(1.0, "km"),
+            Value::new(212.0, "f"),
+        ];
+
+        let all: Vec<_> = readings.into_iter().converted_to("c").collect();
+        assert_eq!(3, all.len());
+        assert!(all[0].is_ok());
+        assert!(all[1].is_err());
+        assert!(all[2].is_ok());
+    }
+}
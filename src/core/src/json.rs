@@ -0,0 +1,602 @@
+//! JSON serialization for values, units and families (the `serde` feature).
+//!
+//! serde isn't a dependency of this crate, so this is a small hand-rolled
+//! encoder and parser covering just what these types need. The formats match
+//! what serde's derives would produce (e.g. `{"quantity": 100.0, "unit": "c"}`),
+//! so switching to serde later wouldn't change them.
+use super::prelude::{Family, ParseValueError, PhysicalConstraint, RangeBehavior, Unit, Value};
+use std::iter::Peekable;
+use std::result;
+use std::str::Chars;
+
+type Result<T> = result::Result<T, ParseValueError>;
+
+impl Value {
+    /// Returns the JSON representation of the value, e.g.
+    /// `{"quantity": 100.0, "unit": "c"}`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let v = core::Value::new(100.0, "c");
+    /// assert_eq!(r#"{"quantity": 100.0, "unit": "c"}"#, v.to_json());
+    /// assert_eq!(v, core::Value::from_json(&v.to_json()).unwrap());
+    /// ```
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"quantity": {}, "unit": {}}}"#,
+            number(self.quantity),
+            string(&self.unit)
+        )
+    }
+
+    /// Parses a value from its JSON representation (see `Value::to_json`). The
+    /// value can be converted as is.
+    pub fn from_json(s: &str) -> Result<Self> {
+        Self::from_object(&Json::parse(s)?)
+    }
+
+    fn from_object(json: &Json) -> Result<Self> {
+        let obj = Object::new(json, "value")?;
+        Ok(Self::new(obj.number("quantity")?, obj.string("unit")?))
+    }
+}
+
+impl Unit {
+    /// Returns the JSON representation of the unit, with a field for each of its
+    /// public fields (e.g. `{"names": ["meter", "meters"], "symbol": "m", ...}`).
+    pub fn to_json(&self) -> String {
+        let names: Vec<String> = self.names.iter().map(|n| string(n)).collect();
+        format!(
+            r#"{{"names": [{}], "symbol": {}, "ratio": {}, "difference": {}, "description": {}, "system": {}, "case_sensitive": {}}}"#,
+            names.join(", "),
+            string(&self.symbol),
+            number(self.ratio),
+            number(self.difference),
+            optional_string(self.description.as_deref()),
+            optional_string(self.system.as_deref()),
+            self.case_sensitive
+        )
+    }
+
+    /// Parses a unit from its JSON representation (see `Unit::to_json`). The
+    /// description, system and case_sensitive fields may be left out.
+    ///
+    /// Returns an error when the ratio is zero, where `Unit::new` would panic.
+    pub fn from_json(s: &str) -> Result<Self> {
+        Self::from_object(&Json::parse(s)?)
+    }
+
+    fn from_object(json: &Json) -> Result<Self> {
+        let obj = Object::new(json, "unit")?;
+        let names = obj
+            .array("names")?
+            .iter()
+            .map(|n| n.as_str().ok_or_else(|| invalid("names")))
+            .collect::<Result<Vec<&str>>>()?;
+        let symbol = obj.string("symbol")?;
+        let (ratio, difference) = (obj.number("ratio")?, obj.number("difference")?);
+        if ratio == 0.0 {
+            return Err(invalid("ratio"));
+        }
+
+        let mut unit = Unit::new(names, symbol, ratio, difference);
+        unit.description = obj.optional_string("description")?.map(String::from);
+        unit.system = obj.optional_string("system")?.map(String::from);
+        if obj.optional_bool("case_sensitive")?.unwrap_or_default() {
+            unit = unit.with_case_sensitive_symbol(symbol);
+        }
+
+        Ok(unit)
+    }
+}
+
+impl Family {
+    /// Returns the JSON representation of the family, with a field for each of
+    /// its public fields and its units (see `Unit::to_json`).
+    pub fn to_json(&self) -> String {
+        let units: Vec<String> = self.units().iter().map(Unit::to_json).collect();
+        let constraint = self.constraint.map(|c| match c {
+            PhysicalConstraint::NonNegative => "non_negative",
+        });
+        let range_behavior = match self.range_behavior {
+            RangeBehavior::Clamp => "clamp",
+            RangeBehavior::Wrap => "wrap",
+        };
+
+        format!(
+            r#"{{"id": {}, "base_unit": {}, "units": [{}], "description": {}, "constraint": {}, "max_quantity": {}, "min": {}, "max": {}, "range_behavior": {}, "metric": {}}}"#,
+            string(&self.id),
+            string(&self.base_unit),
+            units.join(", "),
+            optional_string(self.description.as_deref()),
+            optional_string(constraint),
+            optional_number(self.max_quantity),
+            optional_number(self.min),
+            optional_number(self.max),
+            string(range_behavior),
+            self.metric
+        )
+    }
+
+    /// Parses a family from its JSON representation (see `Family::to_json`).
+    /// Only the id, base_unit and units fields are required.
+    ///
+    /// Returns an error when `Family::validate` finds any problems with the
+    /// family (e.g. its base unit isn't the symbol of one of its units).
+    pub fn from_json(s: &str) -> Result<Self> {
+        let json = Json::parse(s)?;
+        let obj = Object::new(&json, "family")?;
+        let units = obj
+            .array("units")?
+            .iter()
+            .map(Unit::from_object)
+            .collect::<Result<Vec<Unit>>>()?;
+
+        let mut family = Family::new_unchecked(obj.string("id")?, obj.string("base_unit")?, units);
+        family.description = obj.optional_string("description")?.map(String::from);
+        family.constraint = match obj.optional_string("constraint")? {
+            Some("non_negative") => Some(PhysicalConstraint::NonNegative),
+            Some(_) => return Err(invalid("constraint")),
+            None => None,
+        };
+        family.max_quantity = obj.optional_number("max_quantity")?;
+        family.min = obj.optional_number("min")?;
+        family.max = obj.optional_number("max")?;
+        family.range_behavior = match obj.optional_string("range_behavior")? {
+            Some("clamp") | None => RangeBehavior::Clamp,
+            Some("wrap") => RangeBehavior::Wrap,
+            Some(_) => return Err(invalid("range_behavior")),
+        };
+        family.metric = obj.optional_bool("metric")?.unwrap_or_default();
+
+        family.validate().map_err(|errs| {
+            let errs: Vec<String> = errs.iter().map(|e| e.to_string()).collect();
+            ParseValueError::new(&format!("invalid family: {}", errs.join(", ")))
+        })?;
+
+        Ok(family)
+    }
+}
+
+/// Returns the JSON representation of a string, escaping it as needed.
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn optional_string(s: Option<&str>) -> String {
+    s.map_or("null".into(), string)
+}
+
+/// Returns the JSON representation of a number. JSON has no representation for
+/// NaN or infinity, so they're written as null.
+fn number(n: f64) -> String {
+    match n.is_finite() {
+        true => format!("{:?}", n),
+        false => "null".into(),
+    }
+}
+
+fn optional_number(n: Option<f64>) -> String {
+    n.map_or("null".into(), number)
+}
+
+fn invalid(field: &str) -> ParseValueError {
+    ParseValueError::new(&format!("invalid JSON field: {}", field))
+}
+
+/// A parsed JSON document.
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses the string as a single JSON document.
+    fn parse(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            chars: s.chars().peekable(),
+        };
+        let json = parser.value()?;
+        match parser.skip_whitespace() {
+            None => Ok(json),
+            Some(c) => Err(parser.unexpected(Some(c))),
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// The fields of a JSON object, with typed accessors that report missing or
+/// invalid fields.
+struct Object<'a>(&'a [(String, Json)]);
+
+impl<'a> Object<'a> {
+    fn new(json: &'a Json, what: &str) -> Result<Self> {
+        match json {
+            Json::Object(fields) => Ok(Self(fields)),
+            _ => Err(ParseValueError::new(&format!(
+                "expected a JSON object for the {}",
+                what
+            ))),
+        }
+    }
+
+    /// Returns the field, treating null the same as a missing field.
+    fn get(&self, key: &str) -> Option<&'a Json> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .filter(|v| **v != Json::Null)
+    }
+
+    fn required(&self, key: &str) -> Result<&'a Json> {
+        self.get(key)
+            .ok_or_else(|| ParseValueError::new(&format!("missing JSON field: {}", key)))
+    }
+
+    fn string(&self, key: &str) -> Result<&'a str> {
+        self.required(key)?.as_str().ok_or_else(|| invalid(key))
+    }
+
+    fn number(&self, key: &str) -> Result<f64> {
+        match self.required(key)? {
+            Json::Number(n) => Ok(*n),
+            _ => Err(invalid(key)),
+        }
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Json]> {
+        match self.required(key)? {
+            Json::Array(items) => Ok(items),
+            _ => Err(invalid(key)),
+        }
+    }
+
+    fn optional_string(&self, key: &str) -> Result<Option<&'a str>> {
+        match self.get(key) {
+            Some(v) => v.as_str().map(Some).ok_or_else(|| invalid(key)),
+            None => Ok(None),
+        }
+    }
+
+    fn optional_number(&self, key: &str) -> Result<Option<f64>> {
+        self.get(key).map(|_| self.number(key)).transpose()
+    }
+
+    fn optional_bool(&self, key: &str) -> Result<Option<bool>> {
+        match self.get(key) {
+            Some(Json::Bool(b)) => Ok(Some(*b)),
+            Some(_) => Err(invalid(key)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A recursive descent parser for JSON (RFC 8259).
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Json> {
+        match self.skip_whitespace() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('n') => self.keyword("null", Json::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.chars.next();
+        let mut fields = vec![];
+        if self.skip_whitespace() == Some('}') {
+            self.chars.next();
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            match self.skip_whitespace() {
+                Some('"') => {}
+                c => return Err(self.unexpected(c)),
+            }
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+
+            match self.skip_whitespace() {
+                Some(',') => _ = self.chars.next(),
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Json::Object(fields));
+                }
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.chars.next();
+        let mut items = vec![];
+        if self.skip_whitespace() == Some(']') {
+            self.chars.next();
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            match self.skip_whitespace() {
+                Some(',') => _ = self.chars.next(),
+                Some(']') => {
+                    self.chars.next();
+                    return Ok(Json::Array(items));
+                }
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.chars.next();
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => s.push(self.escape()?),
+                Some(c) if !c.is_control() => s.push(c),
+                c => return Err(self.unexpected(c)),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char> {
+        Ok(match self.chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let invalid = || ParseValueError::new("invalid JSON escape");
+                let code = match self.hex()? {
+                    // The high half of a surrogate pair, which must be followed by
+                    // an escape for the low half.
+                    high @ 0xd800..=0xdbff => {
+                        if (self.chars.next(), self.chars.next()) != (Some('\\'), Some('u')) {
+                            return Err(invalid());
+                        }
+
+                        match self.hex()? {
+                            low @ 0xdc00..=0xdfff => {
+                                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                            }
+                            _ => return Err(invalid()),
+                        }
+                    }
+                    code => code,
+                };
+                char::from_u32(code).ok_or_else(invalid)?
+            }
+            c => return Err(self.unexpected(c)),
+        })
+    }
+
+    fn hex(&mut self) -> Result<u32> {
+        let digits: String = self.chars.by_ref().take(4).collect();
+        match digits.len() {
+            4 => u32::from_str_radix(&digits, 16)
+                .map_err(|_| ParseValueError::new("invalid JSON escape")),
+            _ => Err(self.unexpected(None)),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let mut s = String::new();
+        while let Some(&c) = self
+            .chars
+            .peek()
+            .filter(|c| c.is_ascii_digit() || "+-.eE".contains(**c))
+        {
+            s.push(c);
+            self.chars.next();
+        }
+
+        s.parse()
+            .map(Json::Number)
+            .map_err(|_| ParseValueError::new(&format!("invalid JSON number: {}", s)))
+    }
+
+    fn keyword(&mut self, word: &str, json: Json) -> Result<Json> {
+        for expected in word.chars() {
+            match self.chars.next() {
+                Some(c) if c == expected => {}
+                c => return Err(self.unexpected(c)),
+            }
+        }
+        Ok(json)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.skip_whitespace() {
+            Some(c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            c => Err(self.unexpected(c)),
+        }
+    }
+
+    /// Skips any whitespace, returning the next character (without consuming it).
+    fn skip_whitespace(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn unexpected(&self, c: Option<char>) -> ParseValueError {
+        match c {
+            Some(c) => ParseValueError::new(&format!("unexpected {:?} in JSON", c)),
+            None => ParseValueError::new("unexpected end of JSON"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widgets() -> Family {
+        Family::new(
+            "Widgets",
+            "w",
+            vec![
+                Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0).with_system("metric"),
+                Unit::new(vec!["kilowidget"], "kw", 1000.0, 0.0)
+                    .with_description("a \"big\" widget"),
+                Unit::new(vec!["megawidget"], "MW", 1e6, 0.0).with_case_sensitive_symbol("MW"),
+            ],
+        )
+        .with_description("things made in a factory")
+        .with_constraint(PhysicalConstraint::NonNegative)
+        .with_range(Some(0.0), None, RangeBehavior::Wrap)
+        .with_metric(true)
+    }
+
+    #[test]
+    fn value() {
+        let v = Value::new(-1.5e-7, "μv");
+        assert_eq!(r#"{"quantity": -1.5e-7, "unit": "μv"}"#, v.to_json());
+        assert_eq!(Ok(v.clone()), Value::from_json(&v.to_json()));
+        assert_eq!(
+            Ok(Value::new(100.0, "c")),
+            Value::from_json(" {\"unit\":\"c\" ,\n\"quantity\":1e2}")
+        );
+        assert_eq!(
+            r#"{"quantity": null, "unit": "c"}"#,
+            Value::new(f64::NAN, "c").to_json()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn value_converts() {
+        let v = Value::from_json(r#"{"quantity": 100.0, "unit": "c"}"#).unwrap();
+        assert_eq!("212.00f", crate::convert(v, "f").unwrap().to_string());
+    }
+
+    #[test]
+    fn unit() {
+        for unit in widgets().units() {
+            let parsed = Unit::from_json(&unit.to_json()).unwrap();
+            assert!(unit.structurally_eq(&parsed), "{}", unit.to_json());
+        }
+
+        assert_eq!(
+            r#"{"names": ["kilowidget"], "symbol": "kw", "ratio": 1000.0, "difference": 0.0, "description": "a \"big\" widget", "system": null, "case_sensitive": false}"#,
+            widgets()[1].to_json()
+        );
+
+        let unit =
+            Unit::from_json(r#"{"names": ["w"], "symbol": "W", "ratio": 1, "difference": 0}"#)
+                .unwrap();
+        assert_eq!(
+            ("w", None, false),
+            (unit.symbol.as_str(), unit.system, unit.case_sensitive)
+        );
+    }
+
+    #[test]
+    fn family() {
+        let fam = widgets();
+        let parsed = Family::from_json(&fam.to_json()).unwrap();
+
+        assert_eq!(fam, parsed);
+        assert_eq!(fam.to_json(), parsed.to_json());
+        assert_eq!("MW", parsed.unit_by_symbol("MW").unwrap().symbol);
+        assert_eq!(
+            Value::new(2000.0, "kw"),
+            parsed.convert(Value::new(2.0, "MW"), "kw").unwrap()
+        );
+
+        let minimal = r#"{"id": "Widgets", "base_unit": "w", "units": [{"names": ["widget"], "symbol": "w", "ratio": 1.0, "difference": 0.0}]}"#;
+        let parsed = Family::from_json(minimal).unwrap();
+        assert_eq!((None, false), (parsed.constraint, parsed.metric));
+        assert_eq!(RangeBehavior::Clamp, parsed.range_behavior);
+    }
+
+    #[test]
+    fn errors() {
+        let err = |res: Result<Value>| res.unwrap_err().to_string();
+        assert_eq!("unexpected end of JSON", err(Value::from_json("")));
+        assert_eq!("unexpected ']' in JSON", err(Value::from_json("{]")));
+        assert_eq!("unexpected 'x' in JSON", err(Value::from_json("{} x")));
+        assert_eq!(
+            "expected a JSON object for the value",
+            err(Value::from_json("[]"))
+        );
+        assert_eq!(
+            "missing JSON field: unit",
+            err(Value::from_json(r#"{"quantity": 1}"#))
+        );
+        assert_eq!(
+            "invalid JSON field: quantity",
+            err(Value::from_json(r#"{"quantity": "1", "unit": "c"}"#))
+        );
+
+        assert_eq!(
+            "invalid JSON field: ratio",
+            Unit::from_json(r#"{"names": ["w"], "symbol": "w", "ratio": 0, "difference": 0}"#)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid family: base unit not found",
+            Family::from_json(r#"{"id": "Widgets", "base_unit": "kw", "units": []}"#)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn strings() {
+        let s = "tab\t \"quoted\" back\\slash \u{1} μ 😀";
+        assert_eq!(r#""tab\t \"quoted\" back\\slash \u0001 μ 😀""#, string(s));
+        assert_eq!(Ok(Json::String(s.into())), Json::parse(&string(s)));
+        assert_eq!(
+            Ok(Json::String("é😀/".into())),
+            Json::parse(r#""\u00e9\ud83d\ude00\/""#)
+        );
+    }
+}
@@ -2,30 +2,59 @@ use super::prelude::{Family, Unit};
 
 /// Returns a Family that converts between units of length (e.g. m, km, ft, etc.).
 pub fn family() -> Family {
-    Family {
-        id: "Lengths".into(),
-        base_unit: "M".into(),
-        units: vec![
+    Family::new(
+        "Lengths",
+        "M",
+        vec![
             // metric units
-            Unit::new(vec!["meter", "meters"], "M", 1.0, 0.0),
-            Unit::new(vec!["centimeter", "centimeters"], "CM", 1.0 / 100.0, 0.0),
-            Unit::new(vec!["millimeter", "millimeters"], "MM", 1.0 / 1000.0, 0.0),
-            Unit::new(vec!["kilometer", "kilometers"], "KM", 1000.0, 0.0),
+            Unit::new(vec!["meter", "meters"], "M", 1.0, 0.0)
+                .with_system("metric")
+                .with_description("the SI base unit of length"),
+            Unit::new(vec!["centimeter", "centimeters"], "CM", 1.0 / 100.0, 0.0)
+                .with_system("metric")
+                .with_description("one hundredth of a meter"),
+            Unit::new(vec!["millimeter", "millimeters"], "MM", 1.0 / 1000.0, 0.0)
+                .with_system("metric")
+                .with_description("one thousandth of a meter"),
+            Unit::new(vec!["kilometer", "kilometers"], "KM", 1000.0, 0.0)
+                .with_system("metric")
+                .with_description("one thousand meters"),
             // imperial units
-            Unit::new(vec!["foot", "feet"], "ft", 0.3048, 0.0),
-            Unit::new(vec!["inch", "inches"], "in", 0.0254, 0.0),
-            Unit::new(vec!["yard", "yards"], "yd", 0.9144, 0.0),
-            Unit::new(vec!["mile", "miles"], "mi", 1609.344, 0.0),
-            Unit::new(vec!["nautical mile", "nautical miles"], "nmi", 1852.0, 0.0),
+            Unit::new(vec!["foot", "feet"], "ft", 0.3048, 0.0)
+                .with_system("imperial")
+                .with_description("twelve inches"),
+            Unit::new(vec!["inch", "inches"], "in", 0.0254, 0.0)
+                .with_system("imperial")
+                .with_description("exactly 2.54 centimeters"),
+            Unit::new(vec!["yard", "yards"], "yd", 0.9144, 0.0)
+                .with_system("imperial")
+                .with_description("three feet"),
+            Unit::new(vec!["mile", "miles"], "mi", 1609.344, 0.0)
+                .with_system("imperial")
+                .with_description("the statute mile of 5,280 feet"),
+            Unit::new(vec!["nautical mile", "nautical miles"], "nmi", 1852.0, 0.0)
+                .with_system("nautical")
+                .with_description("one minute of latitude, used at sea and in the air"),
         ],
-    }
+    )
+    .with_description("distance between two points")
+    .with_metric(true)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prelude::{ConversionError, Result, Value};
     use crate::testutil::assertions::*;
 
+    /// Lengths are compared to a hundred thousandth of the unit.
+    const LENGTH_EPSILON: f64 = 1e-5;
+
+    #[test]
+    fn invariants() {
+        assert_family_invariants(&family());
+    }
+
     #[test]
     fn convert() {
         let fam = family();
@@ -45,4 +74,110 @@ mod tests {
         assert_to_base_unit(&fam, &cases);
         assert_from_base_unit(&fam, &cases);
     }
+
+    #[test]
+    fn common_values() {
+        let fam = family();
+        assert_convert_eq(&fam, "1mi", "km", 1.609344);
+        assert_convert_eq(&fam, "1ft", "in", 12.0);
+        assert_convert_eq(&fam, "3ft", "yd", 1.0);
+        assert_convert_eq(&fam, "1nmi", "m", 1852.0);
+        assert_convert_eq(&fam, "5280ft", "mi", 1.0);
+        assert_convert_eq(&fam, "2.54cm", "in", 1.0);
+    }
+
+    #[test]
+    fn split_by_system() {
+        let fam = family();
+        let systems = fam.split_by_system();
+        let symbols =
+            |system| -> Vec<&str> { systems[system].iter().map(|u| u.symbol.as_str()).collect() };
+
+        assert_eq!(3, systems.len());
+        assert_eq!(vec!["m", "cm", "mm", "km"], symbols("metric"));
+        assert_eq!(vec!["ft", "in", "yd", "mi"], symbols("imperial"));
+        assert_eq!(vec!["nmi"], symbols("nautical"));
+    }
+
+    #[test]
+    fn display_ratio() {
+        let fam = family();
+        let ratio = |sym| fam.unit_by_symbol(sym).unwrap().display_ratio(&fam);
+
+        assert_eq!("1 m = 1 m", ratio("m"));
+        assert_eq!("1 km = 1000 m", ratio("km"));
+        assert_eq!("1 cm = 0.01 m", ratio("cm"));
+        assert_eq!("1 ft = 0.3048 m", ratio("ft"));
+    }
+
+    #[test]
+    fn unit_at() {
+        let fam = family();
+        assert_eq!(9, fam.len());
+        assert!(!fam.is_empty());
+
+        assert_eq!(Some("meter"), fam.unit_at(0).map(|u| u.names[0].as_str()));
+        assert_eq!(Some("nmi"), fam.unit_at(8).map(|u| u.symbol.as_str()));
+        assert_eq!(None, fam.unit_at(9));
+        assert_eq!("km", fam[3].symbol);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let _ = &family()[9];
+    }
+
+    #[test]
+    fn into_iter() {
+        for unit in &family() {
+            assert!(!unit.symbol.is_empty());
+        }
+
+        let fam = family();
+        assert_eq!(fam.units().len(), (&fam).into_iter().count());
+        assert_eq!(
+            fam.units().len(),
+            (&fam).into_iter().collect::<Vec<_>>().len()
+        );
+    }
+
+    #[test]
+    fn conversion_factor() -> Result {
+        let fam = family();
+        assert_quantity_in_delta(3.28084, fam.conversion_factor("m", "ft")?, LENGTH_EPSILON);
+        assert_eq!(1000.0, fam.conversion_factor("km", "meters")?);
+        assert_eq!(1.0, fam.conversion_factor("in", "in")?);
+
+        assert_eq!(
+            Err(ConversionError::unknown_unit("c")),
+            fam.conversion_factor("m", "c")
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    fn unit_conversion_factor_to() {
+        let fam = family();
+        let unit = |sym| fam.unit_by_symbol(sym).unwrap();
+
+        assert_eq!(Some(0.001), unit("m").conversion_factor_to(unit("km")));
+        assert_eq!(Some(1000.0), unit("km").conversion_factor_to(unit("m")));
+        assert_eq!(Some(1.0), unit("ft").conversion_factor_to(unit("ft")));
+    }
+
+    #[test]
+    fn symmetry() {
+        assert_full_symmetry(&family(), LENGTH_EPSILON);
+    }
+
+    #[test]
+    fn nearest_unit() {
+        let fam = family();
+        assert_eq!("mm", fam.nearest_unit(0.001).symbol);
+        assert_eq!("km", fam.nearest_unit(1000.0).symbol);
+        assert_eq!("m", fam.nearest_unit(100.0).symbol);
+        assert_eq!("km", fam.nearest_unit(-2500.0).symbol);
+    }
 }
@@ -1,35 +1,81 @@
 #[macro_use]
 extern crate lazy_static;
-#[macro_use]
 extern crate anyhow;
 extern crate regex;
 
+mod batch;
+mod builder;
+mod currency;
+mod dms;
+mod iter;
+#[cfg(feature = "serde")]
+mod json;
+#[cfg(feature = "length")]
 mod length;
+mod macros;
+mod measurement;
 mod prelude;
+mod registry;
+mod suggest;
+mod table;
+#[cfg(feature = "temperature")]
 mod temperature;
 mod testutil;
+#[cfg(feature = "typed")]
+pub mod typed;
+#[cfg(feature = "voltage")]
+mod voltage;
 
-use prelude::Family;
-pub use prelude::{Result, Unit, Value};
+pub use batch::batch_convert;
+#[cfg(feature = "parallel")]
+pub use batch::batch_convert_par;
+pub use builder::FamilyBuilder;
+pub use currency::CurrencyFamily;
+pub use dms::parse_dms;
+pub use iter::{ConvertedTo, ValueIteratorExt};
+pub use measurement::Measurement;
+pub use prelude::{
+    ConversionError, ConversionResult, DisplayFormat, Family, FamilyValidationError,
+    ParseValueError, PhysicalConstraint, RangeBehavior, Result, Unit, Value,
+};
+pub use registry::{RegistryError, UnitRegistry};
 use std::collections;
+use std::result;
+pub use suggest::{suggest_unit, Suggestions};
+pub use table::{conversion_matrix, family_conversion_table, TableFormat};
 
 lazy_static! {
-    static ref FAMILIES: Vec<Family> = vec![length::family(), temperature::family(),];
+    static ref FAMILIES: UnitRegistry = {
+        let registry = UnitRegistry::new();
+        #[cfg(feature = "length")]
+        registry.register(length::family()).unwrap();
+        #[cfg(feature = "temperature")]
+        registry.register(temperature::family()).unwrap();
+        #[cfg(feature = "voltage")]
+        registry.register(voltage::family()).unwrap();
+        registry
+    };
 }
 
 /// Returns a new Conversion object which can be used to convert the given value
 /// into another unit (via the `to` function).
 ///
+/// An error is returned when the unit of the value isn't known to any family, or
+/// when it can't be converted into `to_unit`.
+///
 /// # Examples
 ///
 /// ```
 /// // Parse a string and convert it.
+/// # #[cfg(feature = "temperature")]
 /// # fn main() -> core::Result {
 /// let val = "100c".parse()?;
 /// println!("{}", core::convert(val, "f")?);
 /// // 212.00f
 /// # core::Value::ok()
 /// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
 /// ```
 ///
 /// ```
@@ -39,26 +85,1084 @@ lazy_static! {
 ///   core::convert(val, "km")
 /// }
 ///
+/// # #[cfg(feature = "length")]
 /// # fn main() -> core::Result {
 /// println!("{}", meters_to_kms(10.0)?);
 /// // 0.01km
 /// # core::Value::ok()
 /// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
 /// ```
 pub fn convert(v: Value, to_unit: &str) -> Result {
+    Ok(FAMILIES.convert(v, to_unit)?)
+}
+
+/// Converts the value into the given unit, returning the fallback when the
+/// conversion fails for any reason. This is handy for rendering, where showing
+/// something is better than handling the error.
+///
+/// # Examples
+///
+/// ```
+/// use core::Value;
+///
+/// let v = core::convert_with_fallback(Value::new(1.0, "km"), "c", Value::new(0.0, "c"));
+/// assert_eq!(Value::new(0.0, "c"), v);
+/// ```
+pub fn convert_with_fallback(v: Value, to_unit: &str, fallback: Value) -> Value {
+    convert(v, to_unit).unwrap_or(fallback)
+}
+
+/// Converts the value into the given unit, returning the value unchanged when
+/// the conversion fails (see `convert_with_fallback`).
+pub fn convert_or_identity(v: Value, to_unit: &str) -> Value {
+    convert_with_fallback(v.clone(), to_unit, v)
+}
+
+/// Converts the value into the given unit, returning both the original and the
+/// converted values.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// let res = core::convert_paired("100c".parse()?, "f")?;
+/// println!("{}", res);
+/// // 100.00c = 212.00f
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+pub fn convert_paired(
+    v: Value,
+    to_unit: &str,
+) -> result::Result<ConversionResult, ConversionError> {
+    Ok(ConversionResult {
+        to: FAMILIES.convert(v.clone(), to_unit)?,
+        from: v,
+    })
+}
+
+/// Parses and evaluates an expression of the form `VALUE TARGET_UNIT` (e.g.
+/// `100c f`). The last token is the target unit and everything before it is
+/// the value, so `100 c f` works too. Parse failures are returned as
+/// `ConversionError::Parse`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// println!("{}", core::parse_and_convert("100c f")?);
+/// // 212.00f
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+pub fn parse_and_convert(expr: &str) -> result::Result<Value, ConversionError> {
+    parse_and_convert_paired(expr).map(|res| res.to)
+}
+
+/// Parses and evaluates an expression like `parse_and_convert`, returning both
+/// the parsed and the converted values.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// let res = core::parse_and_convert_paired("100 c f")?;
+/// assert_eq!(core::Value::new(100.0, "c"), res.from);
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+pub fn parse_and_convert_paired(expr: &str) -> result::Result<ConversionResult, ConversionError> {
+    let expr = expr.trim();
+    let (from, to_unit) = expr.rsplit_once(char::is_whitespace).ok_or_else(|| {
+        ConversionError::Parse(ParseValueError::new(&format!(
+            "expected VALUE TARGET_UNIT, got: {}",
+            expr
+        )))
+    })?;
+
+    let v = from.parse::<Value>().map_err(ConversionError::Parse)?;
+    convert_paired(v, to_unit.trim())
+}
+
+/// Converts the value through each of the units in turn, returning the value in
+/// the last one. Every step must be in the same family as the value, and errors
+/// are wrapped in `ConversionError::ChainStep` to show which step failed.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// println!("{}", core::convert_chain("1mi".parse()?, &["ft", "in", "cm"])?);
+/// // 160934.40cm
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn convert_chain(v: Value, steps: &[&str]) -> result::Result<Value, ConversionError> {
+    steps.iter().enumerate().try_fold(v, |v, (i, to_unit)| {
+        FAMILIES
+            .convert(v, to_unit)
+            .map_err(|e| ConversionError::ChainStep {
+                step: i + 1,
+                to_unit: to_unit.to_string(),
+                source: Box::new(e),
+            })
+    })
+}
+
+/// Converts the value into the given unit and back again, returning how much the
+/// quantity changed along the way (i.e. the precision lost in the round trip).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// let err = core::convert_roundtrip_error("12.5m".parse()?, "ft")?;
+/// assert!(err < 1e-10);
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn convert_roundtrip_error(v: Value, via_unit: &str) -> result::Result<f64, ConversionError> {
+    let there = FAMILIES.convert(v.clone(), via_unit)?;
+    let back = FAMILIES.convert(there, &v.unit)?;
+
+    Ok((v.quantity - back.quantity).abs())
+}
+
+/// Converts the value into every other unit in its family.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// for v in core::convert_all("100c".parse()?)? {
+///     println!("{}", v);
+/// }
+/// // 373.15k
+/// // 212.00f
+/// // 671.67r
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+pub fn convert_all(v: Value) -> result::Result<Vec<Value>, ConversionError> {
+    let fam = find_family(&v.unit)?;
+    let unit = fam.unit_by_symbol(&v.unit).map(|u| &u.symbol);
+
+    fam.units()
+        .iter()
+        .filter(|u| Some(&u.symbol) != unit)
+        .map(|u| fam.convert(v.clone(), &u.symbol))
+        .collect()
+}
+
+/// Returns a cheat sheet of the given quantity of the unit converted into every
+/// other unit of its family, as `(symbol, formatted value)` pairs ordered by the
+/// units' ratios (smallest first). Values are formatted with 4 decimal places.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// for (_, v) in core::conversions_for("km", 1.0)? {
+///     println!("{}", v);
+/// }
+/// // 1000000.0000mm
+/// // 100000.0000cm
+/// // ...
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn conversions_for(
+    unit: &str,
+    quantity: f64,
+) -> result::Result<Vec<(String, String)>, ConversionError> {
+    let fam = find_family(unit)?;
+    let from = fam.unit_by_symbol(unit).map(|u| &u.symbol);
+    let mut others: Vec<&Unit> = fam
+        .units()
+        .iter()
+        .filter(|u| Some(&u.symbol) != from)
+        .collect();
+    others.sort_by(|a, b| a.ratio.total_cmp(&b.ratio));
+
+    others
+        .into_iter()
+        .map(|u| {
+            let v = fam.convert(Value::new(quantity, unit), &u.symbol)?;
+            Ok((u.symbol.clone(), v.with_precision(4)))
+        })
+        .collect()
+}
+
+/// Returns the family shared by all of the values, or None when the slice is
+/// empty, a unit isn't known or the values belong to different families. This
+/// is handy for checking a batch can be converted before doing so.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let values = [core::Value::new(1.0, "m"), core::Value::new(2.0, "ft")];
+/// assert_eq!("Lengths", core::infer_family(&values).unwrap().id);
+/// # }
+/// ```
+pub fn infer_family(values: &[Value]) -> Option<&'static Family> {
+    let (first, rest) = values.split_first()?;
+    let fam = FAMILIES.find(&first.unit)?;
+
+    rest.iter().all(|v| fam.can_convert(&v.unit)).then_some(fam)
+}
+
+/// Returns the symbols of the other units in the unit's family (i.e. the units a
+/// value of the given unit can be converted into), or None when the unit isn't
+/// known.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")] {
+/// let units = core::compatible_units("c").unwrap();
+/// assert_eq!(vec!["k", "f", "r"], units);
+/// # }
+/// ```
+pub fn compatible_units(unit: &str) -> Option<Vec<&'static str>> {
+    let fam = FAMILIES.find(unit)?;
+    let unit = fam.unit_by_symbol(unit)?;
+
+    Some(
+        fam.units()
+            .iter()
+            .filter(|u| u.symbol != unit.symbol)
+            .map(|u| u.symbol.as_str())
+            .collect(),
+    )
+}
+
+/// Converts the value into the unit of its family that gives the most readable
+/// quantity (see `Family::nearest_unit`).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// println!("{}", core::humanize("1500m".parse()?)?);
+/// // 1.50km
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn humanize(v: Value) -> result::Result<Value, ConversionError> {
+    let fam = find_family(&v.unit)?;
+    let base = fam.convert(v.clone(), &fam.base_unit)?;
+    let unit = fam.nearest_unit(base.quantity);
+
+    fam.convert(v, &unit.symbol)
+}
+
+/// Converts the value into the metric unit with the given SI prefix, e.g. `k`
+/// (or `kilo`) turns meters into kilometers. Prefixes are written out in full
+/// or as their symbols (`M` for mega, `m` for milli and `μ` or `u` for micro).
+///
+/// The target is the unit of the value's family whose ratio is the base unit's
+/// scaled by the prefix, so the family must be metric (see `Family::metric`).
+/// An unknown prefix is returned as a `ConversionError::Parse`, and a family that
+/// isn't metric or has no such unit as a `ConversionError::InvalidArgument`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// println!("{}", core::convert_si(core::Value::new(1500.0, "m"), "k")?);
+/// // 1.50km
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn convert_si(v: Value, si_prefix: &str) -> result::Result<Value, ConversionError> {
+    let factor = si_prefix_factor(si_prefix).ok_or_else(|| {
+        let err = ParseValueError::new(&format!("unknown SI prefix: {}", si_prefix));
+        ConversionError::Parse(err)
+    })?;
+
+    let fam = find_family(&v.unit)?;
+    let target = fam
+        .unit_by_symbol(&fam.base_unit)
+        .filter(|_| fam.metric)
+        .and_then(|base| {
+            fam.units()
+                .iter()
+                .filter(|u| u.difference == 0.0)
+                .find(|u| (u.ratio / (base.ratio * factor) - 1.0).abs() < 1e-9)
+        })
+        .ok_or_else(|| {
+            ConversionError::InvalidArgument(format!(
+                "{} has no unit with the SI prefix {}",
+                v.unit, si_prefix
+            ))
+        })?;
+
+    fam.convert(v, &target.symbol)
+}
+
+/// Returns the scale factor of the SI prefix (by name or symbol).
+fn si_prefix_factor(prefix: &str) -> Option<f64> {
+    Some(match prefix {
+        "giga" | "G" => 1e9,
+        "mega" | "M" => 1e6,
+        "kilo" | "k" => 1e3,
+        "" => 1.0,
+        "centi" | "c" => 1e-2,
+        "milli" | "m" => 1e-3,
+        "micro" | "μ" | "u" => 1e-6,
+        _ => return None,
+    })
+}
+
+/// Returns the family containing the given unit.
+fn find_family(unit: &str) -> result::Result<&'static Family, ConversionError> {
     FAMILIES
+        .find(unit)
+        .ok_or_else(|| FAMILIES.unknown_unit(unit))
+}
+
+/// Returns true when the unit is the case sensitive symbol of a registered unit
+/// (e.g. `MV` for megavolts).
+pub(crate) fn find_case_sensitive(unit: &str) -> bool {
+    FAMILIES
+        .families()
         .iter()
-        .find(|f| f.can_convert(&v.unit))
-        .map(|f| f.convert(v, to_unit))
-        .unwrap()
+        .any(|f| f.has_case_sensitive_symbol(unit))
+}
+
+/// Returns the first unit matching the given symbol or name.
+pub(crate) fn find_unit(unit: &str) -> Option<&'static Unit> {
+    FAMILIES
+        .families()
+        .into_iter()
+        .find_map(|f| f.unit_by_symbol(unit))
+}
+
+/// Returns the registered unit matching the symbol or name (case insensitive),
+/// or `ConversionError::UnknownUnit` when there isn't one. The error's message
+/// suggests similar units (see `suggest_unit`).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "length", feature = "temperature"))] {
+/// assert_eq!("c", core::validate_unit("Celsius").unwrap().symbol);
+///
+/// let err = core::validate_unit("kilometre").unwrap_err();
+/// assert!(err.to_string().contains("did you mean: 'kilometer', 'km'"));
+/// # }
+/// ```
+pub fn validate_unit(symbol: &str) -> result::Result<&'static Unit, ConversionError> {
+    find_unit(symbol).ok_or_else(|| FAMILIES.unknown_unit(symbol))
+}
+
+/// Returns a human readable description of the unit, including its family and
+/// how it relates to the family's base unit.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// println!("{}", core::describe_unit("km").unwrap());
+/// // km (kilometer) — part of the Lengths family; base unit: meter; 1 km = 1000 m
+/// # }
+/// ```
+pub fn describe_unit(symbol: &str) -> Option<String> {
+    let fam = FAMILIES.find(symbol)?;
+    let unit = fam.unit_by_symbol(symbol)?;
+    let base = fam.unit_by_symbol(&fam.base_unit)?;
+    let one = fam.convert(Value::one(&unit.symbol), &base.symbol).ok()?;
+
+    Some(format!(
+        "{} ({}) — part of the {} family; base unit: {}; 1 {} = {} {}",
+        unit.symbol, unit.names[0], fam.id, base.names[0], unit.symbol, one.quantity, base.symbol
+    ))
+}
+
+/// Adds the family to the global registry, making its units immediately
+/// available to `convert`, `units`, etc.
+///
+/// # Examples
+///
+/// ```
+/// use core::{Family, Unit, Value};
+///
+/// # fn main() -> core::Result {
+/// core::register_family(Family::new(
+///     "Widgets",
+///     "w",
+///     vec![Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0)],
+/// ))?;
+///
+/// println!("{}", core::convert(Value::new(2.0, "widget"), "widgets")?);
+/// // 2.00widgets
+/// # core::Value::ok()
+/// # }
+/// ```
+pub fn register_family(family: Family) -> result::Result<(), RegistryError> {
+    FAMILIES.register(family)
+}
+
+/// Adds a custom measurement to the global registry, making its units
+/// immediately available to `convert`. See `Measurement` for an example.
+pub fn register_measurement<M>(measurement: M) -> result::Result<(), RegistryError>
+where
+    M: Measurement + 'static,
+{
+    FAMILIES.register_measurement(measurement)
+}
+
+/// Adds the unit to the registered family with the given id, making it
+/// immediately available to `convert`, `units`, etc.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// use core::{Unit, Value};
+///
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// core::register_unit("Lengths", Unit::new(vec!["rack unit", "rack units"], "U", 0.04445, 0.0))?;
+///
+/// println!("{}", core::convert(Value::new(42.0, "U"), "mm")?);
+/// // 1866.90mm
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+pub fn register_unit(family_id: &str, unit: Unit) -> result::Result<(), RegistryError> {
+    FAMILIES.register_unit(family_id, unit)
+}
+
+/// Adds a `CurrencyFamily` built from the given exchange rates (relative to USD)
+/// to the global registry. The rates are fixed at registration time, so this is
+/// meant to be called once at startup.
+///
+/// Returns `RegistryError::InvalidFamily` if any of the rates isn't a positive,
+/// finite number.
+///
+/// # Examples
+///
+/// ```
+/// use core::Value;
+/// use std::collections::HashMap;
+///
+/// # fn main() -> core::Result {
+/// core::register_currency_family(HashMap::from([("EUR".to_string(), 0.5)]))?;
+///
+/// println!("{}", core::convert(Value::new(10.0, "eur"), "usd")?);
+/// // 20.00usd
+/// # core::Value::ok()
+/// # }
+/// ```
+pub fn register_currency_family(
+    rates: collections::HashMap<String, f64>,
+) -> result::Result<(), RegistryError> {
+    FAMILIES.register_measurement(CurrencyFamily::new(rates)?)
+}
+
+/// Returns the symbols of every registered unit, sorted and without duplicates.
+pub fn all_unit_symbols() -> Vec<&'static str> {
+    sorted_unique(
+        FAMILIES
+            .families()
+            .into_iter()
+            .flat_map(|f| f.units().iter().map(|u| u.symbol.as_str())),
+    )
+}
+
+/// Returns the names of every registered unit, sorted and without duplicates.
+pub fn all_unit_names() -> Vec<&'static str> {
+    sorted_unique(FAMILIES.families().into_iter().flat_map(|f| {
+        f.units()
+            .iter()
+            .flat_map(|u| u.names.iter().map(String::as_str))
+    }))
+}
+
+fn sorted_unique(iter: impl Iterator<Item = &'static str>) -> Vec<&'static str> {
+    let mut all: Vec<&str> = iter.collect();
+    all.sort_unstable();
+    all.dedup();
+    all
+}
+
+/// Returns the number of units across all registered families.
+pub fn unit_count() -> usize {
+    FAMILIES.families().iter().map(|f| f.units().len()).sum()
+}
+
+/// Returns the number of registered families.
+pub fn family_count() -> usize {
+    FAMILIES.families().len()
+}
+
+/// Returns the units of the family with the given id (case insensitive), from
+/// the smallest to the largest (e.g. mm, cm, in, ...).
+///
+/// Units with a difference (e.g. celsius) are sorted by `ratio * (1 +
+/// |difference|)`, which keeps the order deterministic but isn't necessarily
+/// physically meaningful. Units with the same key keep their defined order.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let units = core::units_sorted_by_ratio("Lengths").unwrap();
+/// assert_eq!("mm", units[0].symbol);
+/// # }
+/// ```
+pub fn units_sorted_by_ratio(
+    family_id: &str,
+) -> result::Result<Vec<&'static Unit>, ConversionError> {
+    let fam = FAMILIES
+        .find_by_id(family_id)
+        .ok_or_else(|| ConversionError::UnknownFamily(family_id.into()))?;
+
+    let key = |u: &Unit| u.ratio * (1.0 + u.difference.abs());
+    let mut units: Vec<&Unit> = fam.units().iter().collect();
+    units.sort_by(|a, b| key(a).total_cmp(&key(b)));
+    Ok(units)
 }
 
 /// Returns all available units in this library, keyed by the family (e.g. length, temp, etc.).
-pub fn units<'a>() -> collections::HashMap<&'a str, &'a Vec<Unit>> {
+///
+/// Families are sorted by their IDs.
+pub fn units<'a>() -> collections::BTreeMap<&'a str, &'a [Unit]> {
     FAMILIES
-        .iter()
-        .fold(collections::HashMap::new(), |mut acc, f| {
-            _ = acc.insert(&f.id, &f.units);
+        .families()
+        .into_iter()
+        .fold(collections::BTreeMap::new(), |mut acc, f| {
+            _ = acc.insert(&f.id, f.units());
             acc
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_unknown_unit() {
+        let err = super::convert(Value::new(1.0, "xyz"), "abc").unwrap_err();
+        assert_eq!("unknown unit: xyz", err.to_string());
+        assert_eq!(
+            Some(&ConversionError::unknown_unit("xyz")),
+            err.downcast_ref::<ConversionError>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_suggestions() {
+        let err = super::convert(Value::new(1.0, "kilometre"), "feet").unwrap_err();
+        assert_eq!(
+            "unknown unit 'kilometre'; did you mean: 'kilometer', 'km'?",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_incompatible_unit() {
+        assert!(super::convert(Value::new(1.0, "km"), "abc").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn convert_paired() -> Result {
+        let res = super::convert_paired("100c".parse()?, "f")?;
+        assert_eq!(Value::new(100.0, "c"), res.from);
+        assert_eq!("f", res.to.unit);
+        assert_eq!("100.00c = 212.00f", res.to_string());
+
+        let res = super::convert_paired("2km".parse()?, "m")?;
+        assert_eq!(1000.0, res.factor());
+
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            super::convert_paired(Value::new(1.0, "xyz"), "m")
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn convert_with_fallback() {
+        let fallback = Value::new(-1.0, "?");
+        let convert = |v, to| super::convert_with_fallback(v, to, fallback.clone());
+
+        assert_eq!(Value::new(1000.0, "m"), convert(Value::new(1.0, "km"), "m"));
+        assert_eq!(fallback, convert(Value::new(1.0, "km"), "c"));
+        assert_eq!(fallback, convert(Value::new(1.0, "xyz"), "m"));
+        assert_eq!(fallback, convert(Value::new(1.0, "m"), "xyz"));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_or_identity() {
+        assert_eq!(
+            Value::new(1000.0, "m"),
+            super::convert_or_identity(Value::new(1.0, "km"), "m")
+        );
+        assert_eq!(
+            Value::new(1.0, "km"),
+            super::convert_or_identity(Value::new(1.0, "km"), "xyz")
+        );
+        assert_eq!(
+            Value::new(2.5, "xyz"),
+            super::convert_or_identity(Value::new(2.5, "xyz"), "m")
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn convert_all() -> Result {
+        let all = super::convert_all("100c".parse()?)?;
+        assert_eq!(
+            vec!["k", "f", "r"],
+            all.iter().map(|v| &v.unit).collect::<Vec<_>>()
+        );
+
+        let all = super::convert_all("1 meter".parse()?)?;
+        assert_eq!(8, all.len());
+        assert!(all.iter().all(|v| v.unit != "m"));
+
+        assert!(super::convert_all(Value::new(1.0, "xyz")).is_err());
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn units_sorted() {
+        let ids: Vec<&str> = units().into_keys().collect();
+        assert_eq!(ids, units().into_keys().collect::<Vec<_>>());
+
+        let lengths = ids.iter().position(|id| *id == "Lengths");
+        let temps = ids.iter().position(|id| *id == "Temperature");
+        assert!(lengths < temps);
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn units_sorted_by_ratio() {
+        let symbols = |id| -> Vec<&str> {
+            super::units_sorted_by_ratio(id)
+                .unwrap()
+                .into_iter()
+                .map(|u| u.symbol.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            vec!["mm", "cm", "in", "ft", "yd", "m", "km", "mi", "nmi"],
+            symbols("lengths")
+        );
+        assert_eq!(vec!["r", "k", "f", "c"], symbols("Temperature"));
+        assert_eq!(symbols("Temperature"), symbols("Temperature"));
+        assert_eq!(
+            Err(ConversionError::UnknownFamily("Widgets".into())),
+            super::units_sorted_by_ratio("Widgets")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn parse_and_convert() -> Result {
+        let v = super::parse_and_convert("100c f")?;
+        assert!((v.quantity - 212.0).abs() < 1e-9);
+        assert_eq!("f", v.unit);
+        assert_eq!(v, super::parse_and_convert("  100 c   f ")?);
+
+        for expr in ["100 f", "100c", "", "abc f"] {
+            let err = super::parse_and_convert(expr).unwrap_err();
+            assert!(
+                matches!(err, ConversionError::Parse(_)),
+                "{}: {}",
+                expr,
+                err
+            );
+        }
+
+        assert_eq!(
+            "failed to parse: expected VALUE TARGET_UNIT, got: 100c",
+            super::parse_and_convert("100c").unwrap_err().to_string()
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            super::parse_and_convert("100c xyz")
+        );
+
+        let res = super::parse_and_convert_paired("100c f")?;
+        assert_eq!((Value::new(100.0, "c"), v), (res.from, res.to));
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_chain() -> Result {
+        let v = super::convert_chain(Value::new(1.0, "km"), &["m", "cm", "mm"])?;
+        assert_eq!(Value::new(1_000_000.0, "mm"), v);
+
+        let v = super::convert_chain(Value::new(1.0, "mi"), &["ft", "in", "cm"])?;
+        assert!((v.quantity - 160_934.4).abs() < 1e-6);
+        assert_eq!(
+            Value::new(2.0, "m"),
+            super::convert_chain(Value::new(2.0, "m"), &[])?
+        );
+
+        let err = super::convert_chain(Value::new(1.0, "km"), &["m", "xyz", "cm"]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConversionError::ChainStep { step: 2, ref to_unit, .. } if to_unit == "xyz"
+        ));
+        assert!(err.to_string().starts_with("step 2 (to xyz) failed: "));
+        assert!(std::error::Error::source(&err).is_some());
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_roundtrip_error() -> Result {
+        assert!(super::convert_roundtrip_error(Value::new(1.0, "m"), "ft")? < 1e-10);
+        assert!(super::convert_roundtrip_error(Value::new(123.456, "ft"), "m")? < 1e-10);
+        assert_eq!(
+            0.0,
+            super::convert_roundtrip_error(Value::new(5.0, "km"), "km")?
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            super::convert_roundtrip_error(Value::new(1.0, "xyz"), "m")
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn compatible_units() {
+        let units = super::compatible_units("km").unwrap();
+        assert_eq!(8, units.len());
+        assert!(["m", "ft", "mi"].iter().all(|u| units.contains(u)));
+        assert!(!units.contains(&"km"));
+        assert_eq!(units, super::compatible_units("Kilometers").unwrap());
+
+        assert_eq!(Some(vec!["k", "f", "r"]), super::compatible_units("c"));
+        assert_eq!(None, super::compatible_units("xyz"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn infer_family() {
+        let id = |values: &[Value]| super::infer_family(values).map(|f| f.id.as_str());
+        let lengths = [
+            Value::new(1.0, "m"),
+            Value::new(100.0, "cm"),
+            Value::new(10.0, "km"),
+        ];
+
+        assert_eq!(Some("Lengths"), id(&lengths));
+        assert_eq!(Some("Lengths"), id(&lengths[..1]));
+        assert_eq!(
+            Some("Temperature"),
+            id(&[Value::new(1.0, "c"), Value::new(100.0, "f")])
+        );
+        assert_eq!(None, id(&[Value::new(1.0, "m"), Value::new(1.0, "c")]));
+        assert_eq!(None, id(&[Value::new(1.0, "m"), Value::new(1.0, "xyz")]));
+        assert_eq!(None, id(&[Value::new(1.0, "xyz")]));
+        assert_eq!(None, id(&[]));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn conversions_for() {
+        let sheet = super::conversions_for("km", 1.0).unwrap();
+        let symbols: Vec<&str> = sheet.iter().map(|(s, _)| s.as_str()).collect();
+
+        assert_eq!(8, sheet.len());
+        assert_eq!(
+            vec!["mm", "cm", "in", "ft", "yd", "m", "mi", "nmi"],
+            symbols
+        );
+        assert!(sheet.contains(&("m".into(), "1000.0000m".into())));
+        assert!(sheet.contains(&("mi".into(), "0.6214mi".into())));
+        assert_eq!(sheet, super::conversions_for("kilometers", 1.0).unwrap());
+
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            super::conversions_for("xyz", 1.0)
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn validate_unit() {
+        let km = super::validate_unit("km").unwrap();
+        assert_eq!(("km", "kilometer"), (km.symbol.as_str(), km.primary_name()));
+        assert_eq!("c", super::validate_unit("Celsius").unwrap().symbol);
+        assert_eq!("c", super::validate_unit("C").unwrap().symbol);
+
+        let err = super::validate_unit("kilometre").unwrap_err();
+        assert!(matches!(&err, ConversionError::UnknownUnit { unit, .. } if unit == "kilometre"));
+        assert!(err.suggestion().is_some());
+        assert_eq!(
+            "unknown unit 'kilometre'; did you mean: 'kilometer', 'km'?",
+            err.to_string()
+        );
+        assert!(super::validate_unit("xyz").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn humanize() -> Result {
+        let unit = |qty, unit| super::humanize(Value::new(qty, unit)).unwrap().unit;
+
+        assert_eq!(
+            Value::new(1.0, "mm"),
+            super::humanize(Value::new(0.001, "m"))?
+        );
+        assert_eq!("mm", unit(0.0009, "m"));
+        assert_eq!("cm", unit(0.01, "m"));
+        assert_eq!("m", unit(1.0, "m"));
+        assert_eq!("m", unit(100.0, "m"));
+        assert_eq!("m", unit(10000.0, "cm"));
+        assert_eq!("km", unit(1000.0, "m"));
+        assert_eq!("km", unit(1500.0, "m"));
+        assert_eq!("km", unit(-999_000.0, "m"));
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            super::humanize(Value::new(1.0, "xyz"))
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "voltage")]
+    fn humanize_voltage() {
+        let unit = |qty, unit| super::humanize(Value::new(qty, unit)).unwrap().unit;
+
+        assert_eq!("uv", unit(0.000_5, "v"));
+        assert_eq!("mv", unit(0.5, "v"));
+        assert_eq!("v", unit(230.0, "v"));
+        assert_eq!("kv", unit(11_000.0, "v"));
+        assert_eq!("MV", unit(2.5e6, "v"));
+        assert_eq!("gv", unit(3.0e9, "v"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "voltage"))]
+    fn convert_si() -> Result {
+        let si = |qty, unit, prefix| super::convert_si(Value::new(qty, unit), prefix);
+
+        assert_eq!(Value::new(1.5, "km"), si(1500.0, "m", "k")?);
+        assert_eq!(Value::new(1.5, "km"), si(1500.0, "m", "kilo")?);
+        assert_eq!(Value::new(250.0, "cm"), si(2.5, "m", "centi")?);
+        assert_eq!(Value::new(1_500_000.0, "mm"), si(1.5, "km", "m")?);
+        assert_eq!(Value::new(2.0, "m"), si(2000.0, "mm", "")?);
+        assert_eq!("km", si(1.0, "ft", "k")?.unit);
+
+        // The symbols of the metric units don't have to match the prefix.
+        assert_eq!(Value::new(5.0, "gv"), si(5e9, "v", "G")?);
+        assert_eq!(Value::new(2.0, "MV"), si(2000.0, "kv", "M")?);
+        assert_eq!(Value::new(2.0, "mv"), si(0.002, "v", "m")?);
+        assert_eq!(Value::new(2000.0, "kv"), si(0.002, "gv", "kilo")?);
+        assert_eq!(Value::new(0.5, "mv"), si(500.0, "uv", "milli")?);
+        assert_eq!(Value::new(1000.0, "uv"), si(1.0, "mv", "μ")?);
+
+        assert_eq!(
+            Err(ConversionError::InvalidArgument(
+                "m has no unit with the SI prefix G".into()
+            )),
+            si(1.0, "m", "G")
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            si(1.0, "xyz", "k")
+        );
+        assert_eq!(
+            "failed to parse: unknown SI prefix: hecto",
+            si(1.0, "m", "hecto").unwrap_err().to_string()
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn convert_si_non_metric_base() {
+        assert_eq!(
+            Err(ConversionError::InvalidArgument(
+                "c has no unit with the SI prefix k".into()
+            )),
+            super::convert_si(Value::new(1.0, "c"), "k")
+        );
+        assert!(!crate::temperature::family().metric);
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn describe_unit() {
+        assert_eq!(
+            Some("km (kilometer) — part of the Lengths family; base unit: meter; 1 km = 1000 m"),
+            super::describe_unit("KM").as_deref()
+        );
+
+        let desc = super::describe_unit("celsius").unwrap();
+        assert!(desc.contains("base unit: kelvin; 1 c = 274.15 k"));
+
+        assert_eq!(None, super::describe_unit("xyz"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn all_unit_symbols() {
+        let symbols = super::all_unit_symbols();
+        for sym in ["m", "km", "ft", "c", "f", "k"] {
+            assert!(symbols.contains(&sym), "missing {}", sym);
+        }
+
+        let mut unique = symbols.clone();
+        unique.dedup();
+        assert_eq!(unique, symbols);
+        assert!(symbols.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn all_unit_names() {
+        let names = super::all_unit_names();
+        for name in ["meter", "kilometers", "feet", "celsius"] {
+            assert!(names.contains(&name), "missing {}", name);
+        }
+
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn counts() -> Result {
+        assert!(family_count() >= 2);
+        assert!(unit_count() >= 13);
+
+        let (families, units) = (family_count(), unit_count());
+        super::register_family(Family::new(
+            "Count Widgets",
+            "cw",
+            vec![Unit::new(vec!["count widget"], "cw", 1.0, 0.0)],
+        ))?;
+
+        assert!(family_count() > families);
+        assert!(unit_count() > units);
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn register_family() -> Result {
+        super::register_family(Family::new(
+            "Test Widgets",
+            "tw",
+            vec![
+                Unit::new(vec!["test widget", "test widgets"], "tw", 1.0, 0.0),
+                Unit::new(vec!["kilo test widget"], "ktw", 1000.0, 0.0),
+            ],
+        ))?;
+
+        let v = super::convert(Value::new(1.0, "test widget"), "test widgets")?;
+        assert_eq!(Value::new(1.0, "test widgets"), v);
+
+        let v = super::convert(Value::new(1.0, "ktw"), "tw")?;
+        assert_eq!(Value::new(1000.0, "tw"), v);
+        assert!(units().contains_key("Test Widgets"));
+
+        assert_eq!(
+            Err(RegistryError::DuplicateId("Lengths".into())),
+            super::register_family(length::family())
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    fn register_currency_family() -> Result {
+        let rates =
+            || collections::HashMap::from([("EUR".to_string(), 0.92), ("JPY".to_string(), 150.0)]);
+        super::register_currency_family(rates())?;
+
+        let v = super::convert(Value::new(92.0, "eur"), "jpy")?;
+        assert_eq!(Value::new(15000.0, "jpy"), v);
+        assert!(units().contains_key("Currencies"));
+
+        assert_eq!(
+            Err(RegistryError::DuplicateId("Currencies".into())),
+            super::register_currency_family(rates())
+        );
+        assert_eq!(
+            Err(RegistryError::InvalidFamily(
+                "invalid ratio for unit: eur".into()
+            )),
+            super::register_currency_family(collections::HashMap::from([(
+                "EUR".to_string(),
+                -1.0
+            )]))
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", not(feature = "temperature")))]
+    fn slim_build() -> Result {
+        let v = super::convert(Value::new(1.0, "km"), "m")?;
+        assert_eq!(Value::new(1000.0, "m"), v);
+
+        assert!(units().contains_key("Lengths"));
+        assert!(!units().contains_key("Temperature"));
+        assert!(matches!(
+            super::convert_paired(Value::new(100.0, "c"), "f"),
+            Err(ConversionError::UnknownUnit { unit, .. }) if unit == "c"
+        ));
+
+        Value::ok()
+    }
+}
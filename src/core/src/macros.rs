@@ -0,0 +1,75 @@
+/// Constructs a Value from a quantity and unit.
+///
+/// # Examples
+///
+/// ```
+/// use core::{value, Value};
+///
+/// assert_eq!(Value::new(100.0, "km"), value!(100.0 km));
+/// assert_eq!(Value::new(5.0, "ft"), value!(5 ft));
+/// assert_eq!(Value::new(-10.0, "c"), value!(-10 c));
+/// ```
+#[macro_export]
+macro_rules! value {
+    (- $qty:literal $unit:ident) => {{
+        #[allow(clippy::unnecessary_cast)]
+        let qty = -($qty as f64);
+        $crate::Value::new(qty, stringify!($unit))
+    }};
+    ($qty:literal $unit:ident) => {{
+        #[allow(clippy::unnecessary_cast)]
+        let qty = $qty as f64;
+        $crate::Value::new(qty, stringify!($unit))
+    }};
+}
+
+/// Converts a quantity and unit into another unit, returning a `core::Result`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")]
+/// # fn main() -> core::Result {
+/// println!("{}", core::convert!(100.0 c => f)?);
+/// // 212.00f
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "temperature"))]
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! convert {
+    (- $qty:literal $unit:ident => $to:ident) => {
+        $crate::convert($crate::value!(-$qty $unit), stringify!($to))
+    };
+    ($qty:literal $unit:ident => $to:ident) => {
+        $crate::convert($crate::value!($qty $unit), stringify!($to))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::Value;
+
+    #[test]
+    fn value() {
+        assert_eq!(Value::new(100.0, "km"), value!(100.0 km));
+        assert_eq!(Value::new(5.0, "ft"), value!(5 ft));
+        assert_eq!(Value::new(-10.0, "c"), value!(-10 c));
+        assert_eq!(Value::new(-2.5, "m"), value!(-2.5 m));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn convert() -> crate::Result {
+        assert_eq!(
+            crate::convert(Value::new(100.0, "c"), "f")?,
+            convert!(100.0 c => f)?
+        );
+        assert_eq!(Value::new(1000.0, "m"), convert!(1 km => m)?);
+        assert_eq!(Value::new(-1000.0, "m"), convert!(-1 km => m)?);
+        assert!(convert!(1 km => c).is_err());
+
+        Value::ok()
+    }
+}
@@ -0,0 +1,77 @@
+use super::prelude::{ConversionError, Family, Value};
+use std::result;
+
+/// A system of units that values can be converted between.
+///
+/// Family implements this for the usual ratio/difference conversions, but other
+/// types can implement it for conversions that don't fit that model (e.g.
+/// logarithmic scales) and register them with `core::register_measurement`.
+///
+/// # Examples
+///
+/// ```
+/// use core::{ConversionError, Measurement, Value};
+///
+/// /// Converts between decibels and power ratios.
+/// struct Decibels;
+///
+/// impl Measurement for Decibels {
+///     fn id(&self) -> &str {
+///         "Decibels"
+///     }
+///
+///     fn can_convert(&self, unit: &str) -> bool {
+///         unit == "db" || unit == "ratio"
+///     }
+///
+///     fn convert(&self, v: Value, to_unit: &str) -> Result<Value, ConversionError> {
+///         match (v.unit.as_str(), to_unit) {
+///             (from, to) if from == to => Ok(v),
+///             ("db", "ratio") => Ok(Value::new(10f64.powf(v.quantity / 10.0), to_unit)),
+///             ("ratio", "db") => Ok(Value::new(10.0 * v.quantity.log10(), to_unit)),
+///             _ => Err(ConversionError::unknown_unit(to_unit)),
+///         }
+///     }
+/// }
+///
+/// # fn main() -> core::Result {
+/// core::register_measurement(Decibels)?;
+/// println!("{}", core::convert(Value::new(20.0, "db"), "ratio")?);
+/// // 100.00ratio
+/// # core::Value::ok()
+/// # }
+/// ```
+pub trait Measurement: Send + Sync {
+    /// Returns the unique id of the measurement (e.g. `Lengths`).
+    fn id(&self) -> &str;
+
+    /// Returns true when the measurement contains the specified unit.
+    fn can_convert(&self, unit: &str) -> bool;
+
+    /// Converts the value into the specified unit.
+    fn convert(&self, v: Value, to_unit: &str) -> result::Result<Value, ConversionError>;
+
+    /// Returns the Family backing this measurement, if there is one. This is
+    /// used to list the units of a measurement (e.g. by `core::units`).
+    fn family(&self) -> Option<&Family> {
+        None
+    }
+}
+
+impl Measurement for Family {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn can_convert(&self, unit: &str) -> bool {
+        Family::can_convert(self, unit)
+    }
+
+    fn convert(&self, v: Value, to_unit: &str) -> result::Result<Value, ConversionError> {
+        Family::convert(self, v, to_unit)
+    }
+
+    fn family(&self) -> Option<&Family> {
+        Some(self)
+    }
+}
@@ -1,69 +1,511 @@
+use super::suggest::Suggestions;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::iter;
 use std::num;
+use std::ops;
 use std::process;
 use std::result;
+use std::slice;
 
 /// A custom Result for the library.
 pub type Result = anyhow::Result<Value>;
 
 /// A family of measurements (e.g. Lengths, Temperatures, etc.).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct Family {
     pub id: String,
-    pub units: Vec<Unit>,
+    /// The units, in the order they were defined. They're only reachable through
+    /// `Family::units` (or `push_unit`) so the index always matches them.
+    units: Vec<Unit>,
     pub base_unit: String,
+    /// A human readable description of the family.
+    pub description: Option<String>,
+    /// A physical limit that values (in the base unit) must respect.
+    pub constraint: Option<PhysicalConstraint>,
+    /// The largest (absolute) quantity a conversion may produce.
+    pub max_quantity: Option<f64>,
+    /// The smallest value (in the base unit) of the family's natural range.
+    pub min: Option<f64>,
+    /// The largest value (in the base unit) of the family's natural range.
+    pub max: Option<f64>,
+    /// How `clamp_value` brings values into the range.
+    pub range_behavior: RangeBehavior,
+    /// Whether the family's units are scaled by SI prefixes (see
+    /// `core::convert_si`).
+    pub metric: bool,
+    /// Maps each (lowercased) symbol and name to the unit's position in `units`.
+    /// Case sensitive symbols are kept as is.
+    index: HashMap<String, usize>,
 }
 
 impl Family {
+    /// Constructs a new Family from the supplied arguments, indexing the units
+    /// by their symbols and names for quick lookups.
+    ///
+    /// In debug builds this panics when the id is empty or the base unit isn't
+    /// the symbol of one of the units. Use `FamilyBuilder` to get an error
+    /// instead.
+    pub fn new(id: &str, base_unit: &str, units: Vec<Unit>) -> Self {
+        let family = Self::new_unchecked(id, base_unit, units);
+        debug_assert!(!family.id.is_empty(), "family id must not be empty");
+        debug_assert!(
+            family.validate_base_unit_exists().is_ok(),
+            "base unit {} isn't the symbol of any unit in {}",
+            family.base_unit,
+            family.id,
+        );
+
+        family
+    }
+
+    /// Constructs a new Family without checking any of its invariants.
+    pub(crate) fn new_unchecked(id: &str, base_unit: &str, units: Vec<Unit>) -> Self {
+        let mut family = Self {
+            id: id.into(),
+            units: Vec::with_capacity(units.len()),
+            base_unit: base_unit.into(),
+            description: None,
+            constraint: None,
+            max_quantity: None,
+            min: None,
+            max: None,
+            range_behavior: RangeBehavior::Clamp,
+            metric: false,
+            index: HashMap::new(),
+        };
+
+        units.into_iter().for_each(|u| family.push_unit(u));
+        family
+    }
+
+    /// Adds the unit to the end of the family, indexing its symbol and names.
+    pub(crate) fn push_unit(&mut self, unit: Unit) {
+        let i = self.units.len();
+        for key in unit.keys() {
+            // The first unit with a given symbol or name wins.
+            self.index.entry(key).or_insert(i);
+        }
+
+        self.units.push(unit);
+    }
+
+    /// Returns true when a unit of the family is indexed by the key, which must
+    /// already be lowercased (unless it's a case sensitive symbol).
+    pub(crate) fn has_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Sets the human readable description of the family.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the physical constraint enforced by `convert`.
+    pub fn with_constraint(mut self, constraint: PhysicalConstraint) -> Self {
+        self.constraint = Some(constraint);
+        self
+    }
+
+    /// Sets the largest (absolute) quantity `convert` may produce.
+    pub fn with_max_quantity(mut self, max: f64) -> Self {
+        self.max_quantity = Some(max);
+        self
+    }
+
+    /// Marks the family as metric, so its units can be found by SI prefix.
+    pub fn with_metric(mut self, metric: bool) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Sets the natural range of values (in the base unit) used by
+    /// `clamp_value`, and how values are brought into it.
+    pub fn with_range(
+        mut self,
+        min: Option<f64>,
+        max: Option<f64>,
+        behavior: RangeBehavior,
+    ) -> Self {
+        self.min = min;
+        self.max = max;
+        self.range_behavior = behavior;
+        self
+    }
+
+    /// Ensures that the base unit is the symbol of one of the units.
+    pub fn validate_base_unit_exists(&self) -> result::Result<(), FamilyValidationError> {
+        match self.units.iter().any(|u| u.has_symbol(&self.base_unit)) {
+            true => Ok(()),
+            false => Err(FamilyValidationError::BaseUnitNotFound),
+        }
+    }
+
+    /// Checks the family for mistakes in its definition (e.g. a missing base unit
+    /// or duplicate symbols), returning every problem found.
+    pub fn validate(&self) -> result::Result<(), Vec<FamilyValidationError>> {
+        let mut errors = vec![];
+        if self.id.is_empty() {
+            errors.push(FamilyValidationError::EmptyId);
+        } else if self.id.chars().any(char::is_control) {
+            errors.push(FamilyValidationError::InvalidId(self.id.clone()));
+        }
+
+        if self.base_unit.is_empty() {
+            errors.push(FamilyValidationError::EmptyBaseUnit);
+        } else if let Err(e) = self.validate_base_unit_exists() {
+            errors.push(e);
+        }
+
+        let mut symbols = HashSet::new();
+        let mut names = HashSet::new();
+        for unit in &self.units {
+            if !symbols.insert(unit.symbol_key()) {
+                errors.push(FamilyValidationError::DuplicateSymbol(unit.symbol.clone()));
+            }
+
+            unit.names
+                .iter()
+                .filter(|n| !names.insert(*n))
+                .for_each(|n| errors.push(FamilyValidationError::DuplicateName(n.clone())));
+
+            if unit.ratio == 0.0 {
+                errors.push(FamilyValidationError::ZeroRatio(unit.symbol.clone()));
+            } else if !unit.ratio.is_finite() || unit.ratio < 0.0 {
+                errors.push(FamilyValidationError::InvalidRatio(unit.symbol.clone()));
+            }
+
+            if unit.names.is_empty() {
+                errors.push(FamilyValidationError::EmptyNames(unit.symbol.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Returns true when this family contains the specified unit.
     pub fn can_convert(&self, unit: &str) -> bool {
-        self.find_unit(unit).is_some()
+        self.unit_by_symbol(unit).is_some()
     }
 
     /// Converts the value into the specified unit. This is done by first
     /// ensuring that the value is in the base unit, and then converting it
     /// into the target unit.
-    pub fn convert(&self, v: Value, u: &str) -> Result {
-        // Short circuit if the units are the same.
-        if v.unit == u {
-            return Ok(v);
+    ///
+    /// `ConversionError::UnknownUnit` is returned when either unit isn't part of
+    /// the family. An error is also returned when the value (in the base unit)
+    /// violates the family's physical constraint (e.g. temperatures below absolute zero), or
+    /// when the result overflows (is infinite, NaN or larger than `max_quantity`).
+    pub fn convert(&self, v: Value, u: &str) -> result::Result<Value, ConversionError> {
+        // Ensure we're working from the base unit. This also checks the unit
+        // of the value is known.
+        let base_val = self.to_base_unit(v.clone())?;
+
+        if !self.can_convert(u) {
+            return Err(ConversionError::unknown_unit(u));
+        }
+
+        if let Some(PhysicalConstraint::NonNegative) = self.constraint {
+            if base_val.quantity < 0.0 {
+                return Err(ConversionError::PhysicalConstraintViolated);
+            }
+        }
+
+        // Convert to the destination unit, keeping the quantity unchanged when
+        // the units are the same. The result is checked either way.
+        let res = match v.unit == u {
+            true => v.clone(),
+            false => self.to_dest_unit(base_val.quantity, u)?,
+        };
+        let too_large = self.max_quantity.is_some_and(|m| res.quantity.abs() > m);
+        if !res.quantity.is_finite() || too_large {
+            return Err(ConversionError::Overflow {
+                from: v,
+                to_unit: u.into(),
+            });
+        }
+
+        Ok(res)
+    }
+
+    /// Returns the factor `f` such that `from_quantity * f = to_quantity`.
+    ///
+    /// A single factor only exists for linear conversions, so an error is
+    /// returned when either unit has a difference (e.g. celsius).
+    pub fn conversion_factor(&self, from: &str, to: &str) -> result::Result<f64, ConversionError> {
+        let find = |unit: &str| {
+            self.unit_by_symbol(unit)
+                .ok_or_else(|| ConversionError::unknown_unit(unit))
+        };
+
+        let (from, to) = (find(from)?, find(to)?);
+        from.conversion_factor_to(to)
+            .ok_or(ConversionError::NonLinearConversion)
+    }
+
+    /// Returns the unit that gives the most readable quantity for the given
+    /// quantity (in the base unit). Quantities where `1 <= |qty| < 1000` with the
+    /// fewest decimal places are preferred, followed by the one closest to 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the family has no units.
+    pub fn nearest_unit(&self, base_qty: f64) -> &Unit {
+        let score = |u: &Unit| {
+            let qty = (base_qty / u.ratio - u.difference).abs();
+            let readable = (1.0..1000.0).contains(&qty);
+            let decimals = if readable {
+                decimal_places(qty)
+            } else {
+                usize::MAX
+            };
+            (!readable, decimals, qty.ln().abs())
+        };
+
+        self.units
+            .iter()
+            .map(|u| (score(u), u))
+            .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+            .map(|(_, u)| u)
+            .expect("family has no units")
+    }
+
+    /// Returns `steps` evenly spaced values from `from_qty` to `to_qty`
+    /// (inclusive) in the given unit. A single step only includes `from_qty`.
+    ///
+    /// Returns `ConversionError::UnknownUnit` when the unit isn't part of the
+    /// family and `ConversionError::InvalidArgument` when `steps` is zero.
+    ///
+    /// ```
+    /// # let family = core::FamilyBuilder::new("Widgets", "w")
+    /// #     .unit(core::Unit::new(vec!["widget"], "w", 1.0, 0.0))
+    /// #     .build()
+    /// #     .unwrap();
+    /// let values = family.range(0.0, 1.0, 3, "w").unwrap();
+    /// assert_eq!(core::Value::new(0.5, "w"), values[1]);
+    /// ```
+    pub fn range(
+        &self,
+        from_qty: f64,
+        to_qty: f64,
+        steps: usize,
+        unit: &str,
+    ) -> result::Result<Vec<Value>, ConversionError> {
+        if !self.can_convert(unit) {
+            return Err(ConversionError::unknown_unit(unit));
         }
 
-        // Ensure we're working from the base unit.
-        let mut base_val = v.clone();
-        if v.unit != self.base_unit {
-            base_val = self.to_base_unit(v)?;
+        if steps == 0 {
+            return Err(ConversionError::InvalidArgument(
+                "steps must be at least 1".into(),
+            ));
+        }
+
+        let step = match steps {
+            1 => 0.0,
+            n => (to_qty - from_qty) / (n - 1) as f64,
+        };
+
+        Ok((0..steps)
+            .map(|i| Value::new(from_qty + step * i as f64, unit))
+            .collect())
+    }
+
+    /// Brings the value into the family's natural range (see `with_range`),
+    /// returning it in its original unit. The value is converted into the base
+    /// unit first, without enforcing the family's physical constraint, since
+    /// values outside the range are expected.
+    ///
+    /// Wrapping requires both ends of the range, so values are clamped to a
+    /// single bound whatever the behavior is.
+    pub fn clamp_value(&self, v: Value) -> result::Result<Value, ConversionError> {
+        let base = self.to_base_unit(v.clone())?.quantity;
+        let qty = match (self.range_behavior, self.min, self.max) {
+            (RangeBehavior::Wrap, Some(min), Some(max)) if max > min => {
+                min + (base - min).rem_euclid(max - min)
+            }
+            (_, min, max) => {
+                let qty = min.map_or(base, |m| base.max(m));
+                max.map_or(qty, |m| qty.min(m))
+            }
+        };
+
+        if qty == base {
+            return Ok(v);
         }
 
-        // Convert to the destination unit.
-        self.to_dest_unit(base_val.quantity, u)
+        self.to_dest_unit(qty, &v.unit)
     }
 
-    fn to_base_unit(&self, v: Value) -> Result {
-        self.find_unit(&v.unit)
+    fn to_base_unit(&self, v: Value) -> result::Result<Value, ConversionError> {
+        self.unit_by_symbol(&v.unit)
             .map(|u| Value::new((v.quantity + u.difference) * u.ratio, &self.base_unit))
-            .ok_or(anyhow!("unknown unit: {}", &v.unit))
+            .ok_or_else(|| ConversionError::unknown_unit(&v.unit))
     }
 
-    fn to_dest_unit(&self, base_qty: f64, unit: &str) -> Result {
-        self.find_unit(unit)
+    fn to_dest_unit(&self, base_qty: f64, unit: &str) -> result::Result<Value, ConversionError> {
+        // `convert` checks the unit is known first, so this only fails when
+        // it's called directly with an unknown unit.
+        self.unit_by_symbol(unit)
             .map(|c| Value::new(base_qty * (1.0 / c.ratio) - c.difference, unit))
-            .ok_or(anyhow!(
-                "failed to convert {} from {} to {}",
-                base_qty,
-                self.base_unit,
-                unit
-            ))
+            .ok_or(ConversionError::Failed {
+                quantity: base_qty,
+                from: self.base_unit.clone(),
+                to: unit.into(),
+            })
+    }
+
+    /// Returns the unit with the given symbol or name (case insensitive, except
+    /// for case sensitive symbols like `MV`).
+    pub fn unit_by_symbol(&self, unit: &str) -> Option<&Unit> {
+        self.index
+            .get(unit)
+            .or_else(|| self.index.get(&unit.to_lowercase()))
+            .and_then(|&i| self.units.get(i))
     }
 
-    fn find_unit(&self, unit: &str) -> Option<&Unit> {
-        let unit = unit.to_lowercase();
+    /// Returns true when the unit is the case sensitive symbol of one of the
+    /// family's units (e.g. `MV` for megavolts).
+    pub(crate) fn has_case_sensitive_symbol(&self, unit: &str) -> bool {
+        self.index
+            .get(unit)
+            .is_some_and(|&i| self.units[i].case_sensitive && self.units[i].symbol == unit)
+    }
+
+    /// Returns the units of the family grouped by their system of measurement.
+    /// Units without a system are left out.
+    pub fn split_by_system(&self) -> HashMap<String, Vec<&Unit>> {
         self.units
             .iter()
-            .find(|c| c.names.contains(&unit) || c.symbol == unit)
+            .fold(HashMap::new(), |mut acc: HashMap<String, Vec<&Unit>>, u| {
+                if let Some(system) = &u.system {
+                    acc.entry(system.clone()).or_default().push(u);
+                }
+                acc
+            })
+    }
+
+    /// Returns the units of the family, in the order they were defined.
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// Consumes the family, returning its units in the order they were defined.
+    pub fn into_units(self) -> Vec<Unit> {
+        self.units
+    }
+
+    /// Returns the unit at the given position (in the order they were defined).
+    pub fn unit_at(&self, idx: usize) -> Option<&Unit> {
+        self.units.get(idx)
+    }
+
+    /// Returns the number of units in the family.
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Returns true when the family has no units.
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+}
+
+/// Implements fmt::Debug for Family, listing the unit symbols rather than every
+/// detail of each unit.
+impl fmt::Debug for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbols: Vec<&str> = self.units.iter().map(|u| u.symbol.as_str()).collect();
+        f.debug_struct("Family")
+            .field("id", &self.id)
+            .field("base_unit", &self.base_unit)
+            .field("units", &symbols)
+            .finish()
+    }
+}
+
+/// Implements ops::Index for Family, returning the unit at the given position.
+///
+/// # Panics
+///
+/// Panics when the index is out of bounds (see `Family::unit_at` for a
+/// non-panicking alternative).
+impl ops::Index<usize> for Family {
+    type Output = Unit;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.units[idx]
+    }
+}
+
+/// Returns the number of decimal places needed to display the quantity (up to 6).
+fn decimal_places(qty: f64) -> usize {
+    let s = format!("{:.6}", qty);
+    let s = s.trim_end_matches('0');
+    s.len() - s.find('.').map_or(s.len(), |i| i + 1)
+}
+
+/// Implements IntoIterator for &Family, iterating over its units.
+///
+/// ```
+/// # let family = core::FamilyBuilder::new("Widgets", "w")
+/// #     .unit(core::Unit::new(vec!["widget"], "w", 1.0, 0.0))
+/// #     .build()
+/// #     .unwrap();
+/// for unit in &family {
+///     println!("{}", unit.symbol);
+/// }
+/// ```
+impl<'a> IntoIterator for &'a Family {
+    type Item = &'a Unit;
+    type IntoIter = slice::Iter<'a, Unit>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.units.iter()
+    }
+}
+
+/// Implements Extend for Family, adding the units to the end of the family.
+///
+/// As with `Family::new`, the first unit with a given symbol or name wins, so
+/// units that clash with existing ones can't be looked up by that key.
+///
+/// ```
+/// # let mut family = core::FamilyBuilder::new("Widgets", "w")
+/// #     .unit(core::Unit::new(vec!["widget"], "w", 1.0, 0.0))
+/// #     .build()
+/// #     .unwrap();
+/// family.extend(vec![core::Unit::new(vec!["kilowidget"], "kw", 1000.0, 0.0)]);
+/// assert!(family.can_convert("kw"));
+/// ```
+impl Extend<Unit> for Family {
+    fn extend<I: IntoIterator<Item = Unit>>(&mut self, units: I) {
+        units.into_iter().for_each(|u| self.push_unit(u));
     }
 }
 
+/// A physical limit on the values of a family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalConstraint {
+    /// Values in the base unit can't be negative (e.g. below absolute zero).
+    NonNegative,
+}
+
+/// How values outside of a family's natural range are brought into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeBehavior {
+    /// Values are limited to the ends of the range (e.g. kelvin can't go below 0).
+    Clamp,
+    /// Values wrap around from one end to the other (e.g. 400° is 40°).
+    Wrap,
+}
+
 /// Defines a single unit of measurement (within a Family).
 ///
 /// Conversions leverage the ratio and difference fields to convert to and from
@@ -73,9 +515,13 @@ impl Family {
 /// From base: qty * 1.0 / unit.ratio - unit.difference
 ///
 /// See temperature.rs for examples.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Units are equal when their symbols match (case insensitively, unless either
+/// symbol is case sensitive). Use `Unit::structurally_eq` to compare every field.
+#[derive(Debug, Clone)]
 pub struct Unit {
-    /// The singular and plural (optional) names of the unit.
+    /// The singular and plural (optional) names of the unit. When present,
+    /// `names[1]` is always treated as the plural.
     pub names: Vec<String>,
     /// The symbol for the unit (e.g. `m` for meters).
     pub symbol: String,
@@ -83,21 +529,156 @@ pub struct Unit {
     pub ratio: f64,
     /// The difference to add when converting to the base unit.
     pub difference: f64,
+    /// A human readable description of the unit.
+    pub description: Option<String>,
+    /// The system of measurement the unit belongs to (e.g. `metric`).
+    pub system: Option<String>,
+    /// Whether the symbol is matched exactly rather than case insensitively (see
+    /// `Unit::with_case_sensitive_symbol`).
+    pub case_sensitive: bool,
 }
 
 impl Unit {
+    /// Constructs a new Unit. The first name is the singular form and the
+    /// second (when supplied) is the plural form of the name.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the ratio is zero or NaN, or when the difference is NaN, since
+    /// conversions using the unit would silently produce infinite or NaN values.
     pub fn new(names: Vec<&str>, sym: &str, ratio: f64, difference: f64) -> Self {
+        assert!(ratio != 0.0, "Unit ratio must be non-zero");
+        assert!(!ratio.is_nan(), "Unit ratio must not be NaN");
+        assert!(!difference.is_nan(), "Unit difference must not be NaN");
+
         Self {
             names: names.iter().map(|n| n.to_lowercase()).collect(),
             symbol: sym.to_lowercase(),
             ratio,
             difference,
+            description: None,
+            system: None,
+            case_sensitive: false,
+        }
+    }
+
+    /// Sets the human readable description of the unit.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the system of measurement the unit belongs to (e.g. `metric`,
+    /// `imperial` or `us_customary`).
+    pub fn with_system(mut self, system: &str) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// Sets a symbol that's matched exactly rather than case insensitively. This
+    /// allows units whose symbols only differ by case, like megavolts (`MV`) and
+    /// millivolts (`mV`, which is then matched by any other case).
+    pub fn with_case_sensitive_symbol(mut self, sym: &str) -> Self {
+        self.symbol = sym.into();
+        self.case_sensitive = true;
+        self
+    }
+
+    /// Returns true when the unit's symbol is the given one, ignoring case unless
+    /// the symbol is case sensitive.
+    pub fn has_symbol(&self, sym: &str) -> bool {
+        match self.case_sensitive {
+            true => self.symbol == sym,
+            false => self.symbol == sym.to_lowercase(),
+        }
+    }
+
+    /// Returns the key the symbol is looked up by: the symbol itself when it's
+    /// case sensitive, or lowercased otherwise.
+    fn symbol_key(&self) -> String {
+        match self.case_sensitive {
+            true => self.symbol.clone(),
+            false => self.symbol.to_lowercase(),
+        }
+    }
+
+    /// Returns the keys the unit is looked up by: its symbol key and its
+    /// (lowercased) names.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        iter::once(self.symbol_key()).chain(self.names.iter().map(|n| n.to_lowercase()))
+    }
+
+    /// Returns how the unit relates to the family's base unit. Linear units are
+    /// shown as `1 km = 1000 m`, while units with a difference are shown as the
+    /// formula for the base quantity (e.g. `k = (c + 273.15) × 1`).
+    pub fn display_ratio(&self, family: &Family) -> String {
+        let base = family
+            .unit_by_symbol(&family.base_unit)
+            .map_or(family.base_unit.to_lowercase(), |u| u.symbol.clone());
+        let num = |n: f64| Value::new(n, "").display_with_format(DisplayFormat::Compact);
+
+        match self.difference {
+            0.0 => format!("1 {} = {} {}", self.symbol, num(self.ratio), base),
+            d => format!(
+                "{} = ({} + {}) × {}",
+                base,
+                self.symbol,
+                num(d),
+                num(self.ratio)
+            ),
+        }
+    }
+
+    /// Returns the singular name of the unit (e.g. `kilometer`).
+    pub fn primary_name(&self) -> &str {
+        &self.names[0]
+    }
+
+    /// Returns true when every field of the units is equal, unlike `==` which
+    /// only compares the symbols.
+    pub fn structurally_eq(&self, other: &Unit) -> bool {
+        self.names == other.names
+            && self.symbol == other.symbol
+            && self.ratio == other.ratio
+            && self.difference == other.difference
+            && self.description == other.description
+            && self.system == other.system
+            && self.case_sensitive == other.case_sensitive
+    }
+
+    /// Returns the name to use when displaying the given quantity. The plural
+    /// name is used unless the magnitude is 1 or there is no plural name.
+    pub fn name_for(&self, quantity: f64) -> &str {
+        match self.names.get(1) {
+            Some(plural) if quantity.abs() != 1.0 => plural,
+            _ => &self.names[0],
+        }
+    }
+
+    /// Returns the factor that quantities of this unit are multiplied by to get
+    /// the quantity in the other unit (e.g. 0.001 for m to km). Returns None when
+    /// either unit has a difference, since the conversion isn't a simple scale.
+    pub fn conversion_factor_to(&self, other: &Unit) -> Option<f64> {
+        match self.difference == 0.0 && other.difference == 0.0 {
+            true => Some(self.ratio / other.ratio),
+            false => None,
+        }
+    }
+}
+
+/// Implements PartialEq for Unit, comparing the symbols case insensitively
+/// unless either of them is case sensitive.
+impl PartialEq for Unit {
+    fn eq(&self, other: &Self) -> bool {
+        match self.case_sensitive || other.case_sensitive {
+            true => self.symbol == other.symbol,
+            false => self.symbol.eq_ignore_ascii_case(&other.symbol),
         }
     }
 }
 
 /// A custom error used to signify errors during parsing.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseValueError {
     description: String,
 }
@@ -105,7 +686,7 @@ pub struct ParseValueError {
 impl ParseValueError {
     /// Creates a new ParseValueError, cloning the supplied message in the
     /// process.
-    fn new(msg: &str) -> Self {
+    pub(crate) fn new(msg: &str) -> Self {
         Self {
             description: msg.into(),
         }
@@ -131,6 +712,176 @@ impl From<num::ParseFloatError> for ParseValueError {
     }
 }
 
+/// A problem found while validating a family definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FamilyValidationError {
+    /// The family has an empty id.
+    EmptyId,
+    /// The family id contains control characters (e.g. newlines).
+    InvalidId(String),
+    /// The family has an empty base unit.
+    EmptyBaseUnit,
+    /// The base unit isn't one of the family's units.
+    BaseUnitNotFound,
+    /// More than one unit uses the symbol.
+    DuplicateSymbol(String),
+    /// More than one unit uses the name.
+    DuplicateName(String),
+    /// The unit (by symbol) has a ratio of zero.
+    ZeroRatio(String),
+    /// The unit (by symbol) has a negative or non-finite ratio.
+    InvalidRatio(String),
+    /// The unit (by symbol) has no names.
+    EmptyNames(String),
+}
+
+/// Marks FamilyValidationError as an Error.
+impl std::error::Error for FamilyValidationError {}
+
+/// Implements fmt::Display for FamilyValidationError.
+impl fmt::Display for FamilyValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyId => write!(f, "empty family id"),
+            Self::InvalidId(id) => write!(f, "invalid family id: {:?}", id),
+            Self::EmptyBaseUnit => write!(f, "empty base unit"),
+            Self::BaseUnitNotFound => write!(f, "base unit not found"),
+            Self::DuplicateSymbol(sym) => write!(f, "duplicate symbol: {}", sym),
+            Self::DuplicateName(name) => write!(f, "duplicate name: {}", name),
+            Self::ZeroRatio(sym) => write!(f, "zero ratio for unit: {}", sym),
+            Self::InvalidRatio(sym) => write!(f, "invalid ratio for unit: {}", sym),
+            Self::EmptyNames(sym) => write!(f, "no names for unit: {}", sym),
+        }
+    }
+}
+
+/// A custom error used to signify errors during conversion.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// The unit isn't known to the family (or library). Errors reported by a
+    /// registry suggest similar units it knows of (e.g. `'kilometer', 'km'`)
+    /// when they're displayed.
+    UnknownUnit {
+        unit: String,
+        suggestions: Suggestions,
+    },
+    /// Both units are known, but they belong to different families (e.g. `km`
+    /// and `c`).
+    IncompatibleUnits { from: String, to: String },
+    /// No family with the id has been registered.
+    UnknownFamily(String),
+    /// The quantity (in the base unit) couldn't be converted to the target unit.
+    Failed {
+        quantity: f64,
+        from: String,
+        to: String,
+    },
+    /// The value violates the physical constraint of its family.
+    PhysicalConstraintViolated,
+    /// The units can't be related by a single scale factor.
+    NonLinearConversion,
+    /// An argument (other than the value being converted) is out of range (e.g.
+    /// a zero reference for a percentage).
+    InvalidArgument(String),
+    /// The converted quantity is too large to be represented meaningfully.
+    Overflow { from: Value, to_unit: String },
+    /// The value or expression couldn't be parsed.
+    Parse(ParseValueError),
+    /// A step (numbered from 1) of a chained conversion failed.
+    ChainStep {
+        step: usize,
+        to_unit: String,
+        source: Box<ConversionError>,
+    },
+}
+
+impl ConversionError {
+    /// Returns an `UnknownUnit` error for the unit, without any suggestions (see
+    /// `UnitRegistry::unknown_unit`).
+    pub fn unknown_unit(unit: &str) -> Self {
+        Self::UnknownUnit {
+            unit: unit.into(),
+            suggestions: Suggestions::default(),
+        }
+    }
+
+    /// Returns the units similar to an unknown one (e.g. `'kilometer', 'km'`),
+    /// or None when there aren't any or this isn't an `UnknownUnit` error. The
+    /// units are searched on every call.
+    pub fn suggestion(&self) -> Option<String> {
+        match self {
+            Self::UnknownUnit { unit, suggestions } => suggestions.describe(unit),
+            _ => None,
+        }
+    }
+}
+
+/// Marks ConversionError as an Error.
+impl std::error::Error for ConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::ChainStep { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Implements fmt::Display for ConversionError.
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownUnit { unit, .. } => match self.suggestion() {
+                Some(hint) => write!(f, "unknown unit '{}'; did you mean: {}?", unit, hint),
+                None => write!(f, "unknown unit: {}", unit),
+            },
+            Self::IncompatibleUnits { from, to } => {
+                write!(
+                    f,
+                    "can't convert {} to {}: they measure different things",
+                    from, to
+                )
+            }
+            Self::UnknownFamily(id) => write!(f, "unknown family: {}", id),
+            Self::Failed { quantity, from, to } => {
+                write!(f, "failed to convert {} from {} to {}", quantity, from, to)
+            }
+            Self::PhysicalConstraintViolated => {
+                write!(f, "value violates the physical constraints of its family")
+            }
+            Self::NonLinearConversion => write!(f, "conversion isn't linear"),
+            Self::InvalidArgument(reason) => write!(f, "invalid argument: {}", reason),
+            Self::Overflow { from, to_unit } => {
+                write!(f, "converting {} to {} overflows", from, to_unit)
+            }
+            Self::Parse(err) => write!(f, "failed to parse: {}", err),
+            Self::ChainStep {
+                step,
+                to_unit,
+                source,
+            } => write!(f, "step {} (to {}) failed: {}", step, to_unit, source),
+        }
+    }
+}
+
+/// The ways a Value can be formatted by `Value::display_with_format`. The
+/// examples are for `1500m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayFormat {
+    /// Two decimal places and the unit symbol (`1500.00m`), like `Display`.
+    Standard,
+    /// As few digits as needed and the unit symbol (`1500m`).
+    Compact,
+    /// Scientific notation with the given number of decimal places (`1.50e3m`).
+    Scientific(usize),
+    /// Two decimal places and the full name of the unit (`1500.00 meters`).
+    FullName,
+    /// The given number of decimal places and the unit symbol (`1500.0m`).
+    Precision(usize),
+    /// Two decimal places, without the unit (`1500.00`).
+    NoUnit,
+}
+
 /// Defines a Value as a quantity and unit.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Value {
@@ -148,10 +899,323 @@ impl Value {
         }
     }
 
+    /// Constructs a new Value from its parts, taking ownership of the unit.
+    pub fn from_parts(quantity: f64, unit: String) -> Self {
+        Self { quantity, unit }
+    }
+
+    /// Consumes the value, returning its quantity and unit.
+    ///
+    /// ```
+    /// let (qty, unit) = core::Value::new(100.0, "km").into_parts();
+    /// assert_eq!((100.0, "km".to_string()), (qty, unit));
+    /// ```
+    pub fn into_parts(self) -> (f64, String) {
+        (self.quantity, self.unit)
+    }
+
+    /// Returns a zero quantity of the given unit.
+    pub fn zero(unit: &str) -> Self {
+        Self::new(0.0, unit)
+    }
+
+    /// Returns a quantity of one of the given unit.
+    pub fn one(unit: &str) -> Self {
+        Self::new(1.0, unit)
+    }
+
+    /// Returns true when the quantity is exactly zero.
+    pub fn is_zero(&self) -> bool {
+        self.quantity == 0.0
+    }
+
+    /// Returns the quantity as an integer when it can be represented exactly,
+    /// and None when it's fractional, infinite, NaN or out of range.
+    pub fn try_as_integer(&self) -> Option<i64> {
+        // i64::MAX isn't representable as an f64, so the upper bound is exclusive.
+        const LIMIT: f64 = 9_223_372_036_854_775_808.0; // 2^63
+        let qty = self.quantity;
+
+        if qty.fract() == 0.0 && (-LIMIT..LIMIT).contains(&qty) {
+            Some(qty as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the absolute value (in the same unit).
+    pub fn abs(&self) -> Self {
+        Self::new(self.quantity.abs(), &self.unit)
+    }
+
+    /// Returns the value with its quantity restricted to the given interval
+    /// (in the same unit).
+    ///
+    /// # Panics
+    ///
+    /// Panics when `min_qty > max_qty` or either of them is NaN (see `f64::clamp`).
+    pub fn clamp(&self, min_qty: f64, max_qty: f64) -> Self {
+        Self::new(self.quantity.clamp(min_qty, max_qty), &self.unit)
+    }
+
+    /// Returns the value with its quantity rounded to `n` significant figures
+    /// (e.g. 123456m to 3 is 123000m). Zero, infinite and NaN quantities, or an
+    /// `n` of zero, leave the value unchanged.
+    pub fn sigfig_round(&self, n: usize) -> Self {
+        let qty = self.quantity;
+        if n == 0 || qty == 0.0 || !qty.is_finite() {
+            return self.clone();
+        }
+
+        let exp = (qty.abs().log10().floor() as i32) - (n as i32 - 1);
+        // Dividing by a negative power of ten (e.g. 1e-6) is inexact, so scale up
+        // by the positive power instead.
+        let rounded = match exp {
+            e if e >= 0 => (qty / 10_f64.powi(e)).round() * 10_f64.powi(e),
+            e => (qty * 10_f64.powi(-e)).round() / 10_f64.powi(-e),
+        };
+
+        Self::new(rounded, &self.unit)
+    }
+
+    /// Returns the value with the function applied to its quantity (in the same unit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "length")]
+    /// # fn main() -> core::Result {
+    /// let v = core::convert("1mi".parse()?, "m")?.map(|q| (q * 10.0).round() / 10.0);
+    /// assert_eq!(core::Value::new(1609.3, "m"), v);
+    /// # core::Value::ok()
+    /// # }
+    /// # #[cfg(not(feature = "length"))]
+    /// # fn main() {}
+    /// ```
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self::new(f(self.quantity), &self.unit)
+    }
+
+    /// Returns the value with its quantity multiplied by `factor` and added to
+    /// `addend` (in the same unit). Uses `f64::mul_add`, which only rounds once.
+    pub fn mul_add(self, factor: f64, addend: f64) -> Self {
+        Self::new(self.quantity.mul_add(factor, addend), &self.unit)
+    }
+
+    /// Calls the (fallible) function with the value, returning its result. This
+    /// is useful for chaining conversions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "length")]
+    /// # fn main() -> core::Result {
+    /// let v = core::Value::new(1.0, "km").and_then(|v| core::convert(v, "m"))?;
+    /// assert_eq!(core::Value::new(1000.0, "m"), v);
+    /// # core::Value::ok()
+    /// # }
+    /// # #[cfg(not(feature = "length"))]
+    /// # fn main() {}
+    /// ```
+    pub fn and_then(self, f: impl FnOnce(Self) -> Result) -> Result {
+        f(self)
+    }
+
+    /// Returns the registered unit matching the value's unit, or None when it isn't
+    /// known (e.g. for `Value::default()`).
+    pub fn to_unit_struct(&self) -> Option<&'static Unit> {
+        crate::find_unit(&self.unit)
+    }
+
+    /// Brings the value into the natural range of its family, e.g. clamping
+    /// `-1k` to `0k` (see `Family::clamp_value`). Values of families without a
+    /// range are returned unchanged.
+    pub fn clamp_to_family_range(&self) -> result::Result<Value, ConversionError> {
+        crate::find_family(&self.unit)?.clamp_value(self.clone())
+    }
+
+    /// Returns true when the value is in the base unit of its family (by symbol
+    /// or name). Values of unknown units are never normalized.
+    pub fn is_normalized(&self) -> bool {
+        crate::find_family(&self.unit)
+            .is_ok_and(|fam| fam.unit_by_symbol(&self.unit) == fam.unit_by_symbol(&fam.base_unit))
+    }
+
+    /// Converts the value into the base unit of its family (e.g. `1000m` for
+    /// `1km`). Values that are already in the base unit are returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "length")]
+    /// # fn main() -> core::Result {
+    /// assert_eq!(core::Value::new(1000.0, "m"), core::Value::new(1.0, "km").normalize()?);
+    /// # core::Value::ok()
+    /// # }
+    /// # #[cfg(not(feature = "length"))]
+    /// # fn main() {}
+    /// ```
+    pub fn normalize(&self) -> result::Result<Value, ConversionError> {
+        let fam = crate::find_family(&self.unit)?;
+        if self.is_normalized() {
+            return Ok(self.clone());
+        }
+
+        // The base unit is matched case insensitively, so use the symbol of the
+        // unit itself (e.g. `m` rather than `M`).
+        let base = fam
+            .unit_by_symbol(&fam.base_unit)
+            .ok_or_else(|| ConversionError::unknown_unit(&fam.base_unit))?;
+        fam.convert(self.clone(), &base.symbol)
+    }
+
+    /// Returns the value formatted with the full name of the unit (e.g. `2.00 meters`).
+    ///
+    /// The unit is printed as is when it isn't known to any family.
+    pub fn display_long(&self) -> String {
+        match self.to_unit_struct() {
+            Some(u) => format!("{:.2} {}", self.quantity, u.name_for(self.quantity)),
+            None => format!("{:.2} {}", self.quantity, self.unit),
+        }
+    }
+
+    /// Returns the value formatted with the given number of decimal places (e.g.
+    /// `212f` for 0 or `212.0000f` for 4).
+    pub fn with_precision(&self, precision: usize) -> String {
+        format!("{:.*}{}", precision, self.quantity, self.unit)
+    }
+
+    /// Returns the value formatted in the given format (see `DisplayFormat`).
+    pub fn display_with_format(&self, fmt: DisplayFormat) -> String {
+        match fmt {
+            DisplayFormat::Standard => self.to_string(),
+            DisplayFormat::Compact => format!("{}{}", self.quantity, self.unit),
+            DisplayFormat::Scientific(p) => format!("{:.*e}{}", p, self.quantity, self.unit),
+            DisplayFormat::FullName => self.display_long(),
+            DisplayFormat::Precision(p) => self.with_precision(p),
+            DisplayFormat::NoUnit => format!("{:.2}", self.quantity),
+        }
+    }
+
+    /// Converts the value into the given unit and formats it with the given
+    /// number of decimal places (e.g. `1.50km` for 1500m in km with 2).
+    pub fn format_as(
+        &self,
+        unit: &str,
+        precision: usize,
+    ) -> result::Result<String, ConversionError> {
+        crate::convert_paired(self.clone(), unit).map(|res| res.to.with_precision(precision))
+    }
+
+    /// Returns the signed difference between the values (`self - other`) in the
+    /// unit of this value, or an error when the other value can't be converted
+    /// into it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "length")]
+    /// # fn main() -> core::Result {
+    /// let v = core::Value::new(1.0, "km").diff(&core::Value::new(250.0, "m"))?;
+    /// assert_eq!(core::Value::new(0.75, "km"), v);
+    /// # core::Value::ok()
+    /// # }
+    /// # #[cfg(not(feature = "length"))]
+    /// # fn main() {}
+    /// ```
+    pub fn diff(&self, other: &Value) -> result::Result<Value, ConversionError> {
+        let other = crate::convert_paired(other.clone(), &self.unit)?.to;
+        Ok(Self::new(self.quantity - other.quantity, &self.unit))
+    }
+
+    /// Returns this value as a percentage of the reference (e.g. `50.0` for 1km
+    /// of 2000m). Both values are converted into the base unit of this value's
+    /// family first, so they must belong to the same family.
+    ///
+    /// Returns `ConversionError::InvalidArgument` when the reference is zero (in
+    /// the base unit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "length")]
+    /// # fn main() -> core::Result {
+    /// let pct = core::Value::new(1.0, "km").percent_of(&core::Value::new(2000.0, "m"))?;
+    /// assert_eq!(50.0, pct);
+    /// # core::Value::ok()
+    /// # }
+    /// # #[cfg(not(feature = "length"))]
+    /// # fn main() {}
+    /// ```
+    pub fn percent_of(&self, reference: &Value) -> result::Result<f64, ConversionError> {
+        let base = &crate::find_family(&self.unit)?.base_unit;
+        let this = crate::convert_paired(self.clone(), base)?.to;
+        let base_ref = crate::convert_paired(reference.clone(), base)?.to;
+
+        if base_ref.quantity == 0.0 {
+            return Err(ConversionError::InvalidArgument(format!(
+                "reference {} is zero",
+                reference
+            )));
+        }
+
+        Ok(this.quantity / base_ref.quantity * 100.0)
+    }
+
+    /// Formats a duration as hours, minutes and seconds (e.g. `1h 23m 45s` for
+    /// 5025s), omitting any parts that are zero. Fractions of a second are shown
+    /// as milliseconds (e.g. `1m 2s 500ms`).
+    ///
+    /// This only works for values whose family has a seconds (`s`) unit.
+    pub fn fmt_duration(&self) -> result::Result<String, ConversionError> {
+        self.fmt_duration_in(crate::UnitRegistry::global())
+    }
+
+    /// Formats a duration (see `Value::fmt_duration`) using the families of the
+    /// given registry.
+    pub(crate) fn fmt_duration_in(
+        &self,
+        registry: &crate::UnitRegistry,
+    ) -> result::Result<String, ConversionError> {
+        let secs = registry.convert(self.clone(), "s")?.quantity;
+        if !secs.is_finite() {
+            return Err(ConversionError::Failed {
+                quantity: self.quantity,
+                from: self.unit.clone(),
+                to: "s".into(),
+            });
+        }
+
+        let mut millis = (secs.abs() * 1000.0).round() as u64;
+        let mut parts = vec![];
+        for (size, suffix) in [(3_600_000, "h"), (60_000, "m"), (1000, "s"), (1, "ms")] {
+            if millis >= size {
+                parts.push(format!("{}{}", millis / size, suffix));
+                millis %= size;
+            }
+        }
+
+        Ok(match parts.is_empty() {
+            true => "0s".into(),
+            false if secs < 0.0 => format!("-{}", parts.join(" ")),
+            false => parts.join(" "),
+        })
+    }
+
     /// Returns a default Result which can be used as a return from main and/or
     /// testing functions.
     pub fn ok() -> Result {
-        Ok(Self::new(0.0, ""))
+        Ok(Self::default())
+    }
+}
+
+/// Implements Default for Value, returning a zero quantity with no unit.
+///
+/// The empty unit isn't part of any family, so the default value can't be
+/// converted. It's only intended to be used as a placeholder.
+impl Default for Value {
+    fn default() -> Self {
+        Self::new(0.0, "")
     }
 }
 
@@ -164,14 +1228,139 @@ impl fmt::Display for Value {
     }
 }
 
-/// Implements str::FromStr for Value.
+/// Implements ops::Add for Value.
 ///
-/// This makes the following possible:
+/// The right hand side is converted into the unit of the left hand side before
+/// being added, so both must belong to the same family.
+///
+/// # Panics
+///
+/// Panics when the right hand side can't be converted into the unit of the left.
 ///
 /// ```
-/// use std::str::FromStr;
+/// # #[cfg(feature = "length")] {
+/// use core::Value;
 ///
-/// let val1 = core::Value::from_str("100c");
+/// let total = Value::new(1.0, "km") + Value::new(500.0, "m");
+/// assert_eq!(Value::new(1.5, "km"), total);
+/// # }
+/// ```
+impl ops::Add for Value {
+    type Output = Value;
+
+    fn add(self, rhs: Value) -> Self::Output {
+        let qty = if rhs.unit == self.unit {
+            rhs.quantity
+        } else {
+            crate::convert(rhs.clone(), &self.unit)
+                .unwrap_or_else(|e| panic!("cannot add {} to {}: {}", rhs, self, e))
+                .quantity
+        };
+
+        Self::new(self.quantity + qty, &self.unit)
+    }
+}
+
+/// Implements iter::Sum for Value.
+///
+/// Every value is added (see ops::Add) to the first one, so all of them must
+/// belong to the same family. Summing an empty iterator returns a zero quantity
+/// with no unit.
+///
+/// # Panics
+///
+/// Panics when a value can't be converted into the unit of the first one.
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// let total: core::Value = vec!["1km".parse()?, "500m".parse()?].into_iter().sum();
+/// println!("{}", total);
+/// // 1.50km
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+impl iter::Sum for Value {
+    fn sum<I: Iterator<Item = Value>>(mut iter: I) -> Self {
+        let first = match iter.next() {
+            Some(v) => v,
+            None => return Self::new(0.0, ""),
+        };
+
+        iter.fold(first, |acc, v| acc + v)
+    }
+}
+
+/// Implements From<Value> for f64, returning the quantity.
+///
+/// This makes the following possible:
+///
+/// ```
+/// # #[cfg(feature = "length")]
+/// # fn main() -> core::Result {
+/// let meters: f64 = core::convert("2km".parse()?, "m")?.into();
+/// assert_eq!(2000.0, meters);
+/// # core::Value::ok()
+/// # }
+/// # #[cfg(not(feature = "length"))]
+/// # fn main() {}
+/// ```
+impl From<Value> for f64 {
+    fn from(v: Value) -> f64 {
+        v.quantity
+    }
+}
+
+/// Implements From<&Value> for f64, returning the quantity.
+impl From<&Value> for f64 {
+    fn from(v: &Value) -> f64 {
+        v.quantity
+    }
+}
+
+/// Implements From<Value> for f32, returning the quantity (which may lose precision).
+impl From<Value> for f32 {
+    fn from(v: Value) -> f32 {
+        v.quantity as f32
+    }
+}
+
+/// Pairs the input and output values of a conversion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionResult {
+    pub from: Value,
+    pub to: Value,
+}
+
+impl ConversionResult {
+    /// Returns the ratio between the output and input quantities.
+    ///
+    /// This is only meaningful for linear conversions (e.g. lengths), since
+    /// conversions with an offset (e.g. temperatures) don't scale uniformly.
+    pub fn factor(&self) -> f64 {
+        self.to.quantity / self.from.quantity
+    }
+}
+
+/// Implements fmt::Display for ConversionResult.
+///
+/// This will print both values separated by an equals sign (e.g. `100.00c = 212.00f`).
+impl fmt::Display for ConversionResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.from, self.to)
+    }
+}
+
+/// Implements str::FromStr for Value.
+///
+/// This makes the following possible:
+///
+/// ```
+/// use std::str::FromStr;
+///
+/// let val1 = core::Value::from_str("100c");
 /// let val2 = "100c".parse::<core::Value>();
 /// ```
 impl std::str::FromStr for Value {
@@ -180,18 +1369,87 @@ impl std::str::FromStr for Value {
     fn from_str(s: &str) -> result::Result<Self, Self::Err> {
         lazy_static! {
             static ref RE: regex::Regex =
-                regex::Regex::new(r"^\s*(-?\d+\.?\d*)\s*([^s]+)\s*$").unwrap();
+                // The unit must start and end with non-whitespace, but may contain
+                // spaces (e.g. `nautical miles`). It can't start with a digit, which
+                // would otherwise split a bare number (e.g. `100` as 10 and `0`).
+                regex::Regex::new(
+                    r"^\s*(-?\d+\.?\d*(?:[eE][-+]?\d+)?)\s*([^\s\d.](?:.*[^\s])?)\s*$"
+                ).unwrap();
+            // Matches units that are really the exponent of a bare number (e.g. the
+            // `e3` of `1.5e3`).
+            static ref EXPONENT: regex::Regex = regex::Regex::new(r"^[eE][-+]?\d").unwrap();
         }
 
-        if let Some(cap) = RE.captures_iter(s).next() {
-            let val = &cap[1].parse::<f64>()?;
-            return Ok(Self::new(*val, &cap[2].to_lowercase()));
+        let non_finite = ParseValueError::new("infinite or NaN quantity not supported");
+        let qty = s.split_whitespace().next().unwrap_or_default();
+        let qty = qty.trim_start_matches(['-', '+']).to_lowercase();
+        if ["inf", "infinity", "nan"].contains(&qty.as_str()) {
+            return Err(non_finite);
+        }
+
+        if let Some(cap) = RE
+            .captures_iter(s)
+            .next()
+            .filter(|c| !EXPONENT.is_match(&c[2]))
+        {
+            let val = cap[1].parse::<f64>()?;
+            if !val.is_finite() {
+                return Err(non_finite);
+            }
+
+            // Units are lowercased, except for case sensitive symbols (e.g. `MV`),
+            // which would otherwise become a different unit.
+            let unit = cap[2].to_lowercase();
+            if unit != cap[2] && crate::find_case_sensitive(&cap[2]) {
+                return Ok(Self::new(val, &cap[2]));
+            }
+
+            return Ok(Self::new(val, &unit));
         }
 
         Err(ParseValueError::new("invalid value"))
     }
 }
 
+/// Implements str::FromStr for Unit, looking up a registered unit by its
+/// symbol or one of its names (case insensitive).
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let km = "km".parse::<core::Unit>().unwrap();
+/// assert_eq!("kilometer", km.names[0]);
+/// # }
+/// ```
+impl std::str::FromStr for Unit {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        crate::find_unit(s.trim())
+            .cloned()
+            .ok_or_else(|| ParseValueError::new(&format!("unknown unit: {}", s)))
+    }
+}
+
+/// Implements str::FromStr for Family, looking up a registered family by its id
+/// (case insensitive).
+///
+/// ```
+/// # #[cfg(feature = "temperature")] {
+/// let fam = "temperature".parse::<core::Family>().unwrap();
+/// assert_eq!("Temperature", fam.id);
+/// # }
+/// ```
+impl std::str::FromStr for Family {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> result::Result<Self, Self::Err> {
+        crate::UnitRegistry::global()
+            .find_by_id(s.trim())
+            .cloned()
+            .ok_or_else(|| ParseValueError::new(&format!("unknown family: {}", s)))
+    }
+}
+
 /// Implements process::Termination for Value.
 ///
 /// This allows main and/or test functions to use the `?` operator.
@@ -223,11 +1481,59 @@ mod tests {
             ("1 m", Value::new(1.0, "m")),
             ("1M", Value::new(1.0, "m")),
             ("-12.3km", Value::new(-12.3, "km")),
+            ("  10 km  ", Value::new(10.0, "km")),
+            ("3 nautical miles", Value::new(3.0, "nautical miles")),
+            // units containing an "s" used to be misparsed
+            ("5ms", Value::new(5.0, "ms")),
+            ("10 lbs", Value::new(10.0, "lbs")),
+            ("24fps", Value::new(24.0, "fps")),
+            ("3.5 rps", Value::new(3.5, "rps")),
+            ("2 miles", Value::new(2.0, "miles")),
         ];
 
-        cases.map(|(given, want)| {
+        _ = cases.map(|(given, want)| {
             assert_eq!(want, given.parse().unwrap());
         });
+
+        assert!("km".parse::<Value>().is_err());
+        assert!("100".parse::<Value>().is_err());
+        assert!("1.5".parse::<Value>().is_err());
+    }
+
+    #[test]
+    fn value_from_str_exponents() {
+        assert_eq!(Value::new(1500.0, "m"), "1.5e3 m".parse().unwrap());
+        assert_eq!(Value::new(-0.002, "km"), "-2E-3km".parse().unwrap());
+        assert_eq!(Value::new(2.0, "e"), "2e".parse().unwrap());
+
+        // An exponent without a unit isn't mistaken for one.
+        let err = Err(ParseValueError::new("invalid value"));
+        for s in ["1.5e3", "1e-3", "2E+10", "1.5 e3"] {
+            assert_eq!(err, s.parse::<Value>(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn value_from_str_non_finite() {
+        let err = Err(ParseValueError::new(
+            "infinite or NaN quantity not supported",
+        ));
+        for given in [
+            "inf m",
+            "-inf m",
+            "infinity km",
+            "nan km",
+            "NaN m",
+            "1e999 m",
+            "-1e400m",
+        ] {
+            assert_eq!(err, given.parse::<Value>(), "{}", given);
+        }
+
+        assert_eq!(
+            Err(ParseValueError::new("invalid value")),
+            "infm".parse::<Value>()
+        );
     }
 
     #[test]
@@ -239,22 +1545,443 @@ mod tests {
         assert_eq!(43.5, unit.difference);
     }
 
+    #[test]
+    #[should_panic(expected = "Unit ratio must be non-zero")]
+    fn unit_zero_ratio() {
+        Unit::new(vec!["test"], "t", 0.0, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unit ratio must not be NaN")]
+    fn unit_nan_ratio() {
+        Unit::new(vec!["test"], "t", f64::NAN, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unit difference must not be NaN")]
+    fn unit_nan_difference() {
+        Unit::new(vec!["test"], "t", 1.0, f64::NAN);
+    }
+
+    #[test]
+    fn unit_description() {
+        let unit = Unit::new(vec!["meter"], "m", 1.0, 0.0);
+        assert_eq!(None, unit.description);
+
+        let unit = unit.with_description("the SI unit of length");
+        assert_eq!(Some("the SI unit of length"), unit.description.as_deref());
+
+        let fam = Family::new("test", "m", vec![unit]).with_description("lengths");
+        assert_eq!(Some("lengths".into()), fam.description);
+    }
+
+    #[test]
+    fn unit_name_for() {
+        let unit = Unit::new(vec!["meter", "meters"], "m", 1.0, 0.0);
+        assert_eq!("meter", unit.name_for(1.0));
+        assert_eq!("meter", unit.name_for(-1.0));
+        assert_eq!("meters", unit.name_for(2.0));
+        assert_eq!("meters", unit.name_for(0.0));
+
+        let unit = Unit::new(vec!["celsius"], "c", 1.0, 273.15);
+        assert_eq!("celsius", unit.name_for(2.0));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_display_long() {
+        assert_eq!("1.00 meter", Value::new(1.0, "m").display_long());
+        assert_eq!("2.00 meters", Value::new(2.0, "m").display_long());
+        assert_eq!("-1.00 meter", Value::new(-1.0, "m").display_long());
+        assert_eq!("5.00 xyz", Value::new(5.0, "xyz").display_long());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn value_normalize() -> Result {
+        assert!(Value::new(1000.0, "m").is_normalized());
+        assert!(Value::new(2.0, "meters").is_normalized());
+        assert!(Value::new(-1.0, "K").is_normalized());
+        assert!(!Value::new(1.0, "km").is_normalized());
+        assert!(!Value::new(1.0, "c").is_normalized());
+        assert!(!Value::new(1.0, "xyz").is_normalized());
+
+        assert_eq!(Value::new(1000.0, "m"), Value::new(1.0, "km").normalize()?);
+        assert_eq!(
+            Value::new(2.0, "meters"),
+            Value::new(2.0, "meters").normalize()?
+        );
+        assert_eq!(Value::new(373.15, "k"), Value::new(100.0, "c").normalize()?);
+        assert!(Value::new(1.0, "m").normalize()?.is_normalized());
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            Value::new(1.0, "xyz").normalize()
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_to_unit_struct() {
+        let unit = Value::new(1.0, "km").to_unit_struct();
+        assert_eq!(Some("kilometer"), unit.map(|u| u.primary_name()));
+        assert_eq!(
+            Some("one thousand meters"),
+            unit.and_then(|u| u.description.as_deref())
+        );
+
+        assert_eq!(None, Value::new(0.0, "").to_unit_struct());
+        assert_eq!(None, Value::default().to_unit_struct());
+        assert_eq!(None, Value::new(1.0, "xyz").to_unit_struct());
+    }
+
+    #[test]
+    fn value_with_precision() {
+        let val = Value::new(212.004, "f");
+        assert_eq!("212f", val.with_precision(0));
+        assert_eq!("212.0f", val.with_precision(1));
+        assert_eq!("212.0040f", val.with_precision(4));
+        assert_eq!(val.to_string(), val.with_precision(2));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_display_with_format() {
+        let v = Value::new(1500.0, "m");
+        let cases = [
+            (DisplayFormat::Standard, "1500.00m"),
+            (DisplayFormat::Compact, "1500m"),
+            (DisplayFormat::Scientific(2), "1.50e3m"),
+            (DisplayFormat::Scientific(0), "2e3m"),
+            (DisplayFormat::FullName, "1500.00 meters"),
+            (DisplayFormat::Precision(1), "1500.0m"),
+            (DisplayFormat::NoUnit, "1500.00"),
+        ];
+
+        for (fmt, want) in cases {
+            assert_eq!(want, v.display_with_format(fmt), "{:?}", fmt);
+        }
+
+        let v = Value::new(0.25, "km");
+        assert_eq!("0.25km", v.display_with_format(DisplayFormat::Compact));
+        assert_eq!(
+            "2.5e-1km",
+            v.display_with_format(DisplayFormat::Scientific(1))
+        );
+    }
+
+    #[test]
+    fn unit_eq() {
+        let unit = Unit::new(vec!["widget"], "w", 1.0, 0.0);
+        let scaled = Unit::new(vec!["big widget"], "W", 1000.0, 5.0);
+
+        assert_eq!(unit, scaled);
+        assert!(!unit.structurally_eq(&scaled));
+        assert!(unit.structurally_eq(&unit.clone()));
+        assert!(!unit.structurally_eq(&unit.clone().with_system("metric")));
+        assert_ne!(unit, Unit::new(vec!["widget"], "kw", 1.0, 0.0));
+
+        let mut upper = unit.clone();
+        upper.symbol = "W".into();
+        assert_eq!(unit, upper);
+        assert!(!unit.structurally_eq(&upper));
+
+        let exact = unit.clone().with_case_sensitive_symbol("W");
+        assert_ne!(unit, exact);
+        assert_eq!(exact, upper.with_case_sensitive_symbol("W"));
+    }
+
+    #[test]
+    fn family_case_sensitive_symbols() {
+        let fam = Family::new(
+            "test",
+            "mw",
+            vec![
+                Unit::new(vec!["milliwidget"], "mW", 1.0, 0.0),
+                Unit::new(vec!["megawidget"], "MW", 1e9, 0.0).with_case_sensitive_symbol("MW"),
+            ],
+        );
+
+        assert_eq!(Ok(()), fam.validate());
+        assert_eq!("MW", fam.unit_by_symbol("MW").unwrap().symbol);
+        assert_eq!("MW", fam.unit_by_symbol("MegaWidget").unwrap().symbol);
+        for sym in ["mW", "mw", "Mw"] {
+            assert_eq!("mw", fam.unit_by_symbol(sym).unwrap().symbol, "{}", sym);
+        }
+
+        assert!(fam.has_case_sensitive_symbol("MW"));
+        assert!(!fam.has_case_sensitive_symbol("mw"));
+        assert_eq!(
+            Value::new(2e9, "mW"),
+            fam.convert(Value::new(2.0, "MW"), "mW").unwrap()
+        );
+
+        // A case sensitive symbol can be the base unit too.
+        let fam = Family::new_unchecked("test", "MW", fam.into_units());
+        assert_eq!(Ok(()), fam.validate_base_unit_exists());
+    }
+
+    #[test]
+    fn family_unit_by_symbol() {
+        let fam = Family::new(
+            "test",
+            "m",
+            vec![
+                Unit::new(vec!["meter", "meters", "metre"], "M", 1.0, 0.0),
+                Unit::new(vec!["kilometer", "kilometers"], "km", 1000.0, 0.0),
+                Unit::new(vec!["kilometre"], "km", 1000.0, 0.0),
+            ],
+        );
+
+        for unit in &fam.units[..2] {
+            assert_eq!(Some(unit), fam.unit_by_symbol(&unit.symbol));
+            unit.names
+                .iter()
+                .for_each(|n| assert_eq!(Some(unit), fam.unit_by_symbol(n)));
+        }
+
+        // Lookups are case insensitive and the first unit with a symbol wins.
+        assert_eq!(Some(&fam.units[0]), fam.unit_by_symbol("METERS"));
+        assert_eq!(Some(&fam.units[0]), fam.unit_by_symbol("Metre"));
+        assert_eq!(Some(&fam.units[1]), fam.unit_by_symbol("km"));
+        assert_eq!(Some(&fam.units[2]), fam.unit_by_symbol("kilometre"));
+        assert_eq!(None, fam.unit_by_symbol("mi"));
+    }
+
+    #[test]
+    fn family_clamp_value() {
+        let angles = |behavior| {
+            Family::new(
+                "test",
+                "deg",
+                vec![
+                    Unit::new(vec!["degree", "degrees"], "deg", 1.0, 0.0),
+                    Unit::new(vec!["turn", "turns"], "tr", 360.0, 0.0),
+                ],
+            )
+            .with_range(Some(0.0), Some(360.0), behavior)
+        };
+
+        let fam = angles(RangeBehavior::Wrap);
+        let clamp = |qty, unit| fam.clamp_value(Value::new(qty, unit)).unwrap();
+        assert_eq!(Value::new(40.0, "deg"), clamp(400.0, "deg"));
+        assert_eq!(Value::new(270.0, "deg"), clamp(-90.0, "deg"));
+        assert_eq!(Value::new(0.0, "deg"), clamp(360.0, "deg"));
+        assert_eq!(Value::new(0.5, "tr"), clamp(2.5, "tr"));
+        assert_eq!(Value::new(90.0, "deg"), clamp(90.0, "deg"));
+
+        let fam = angles(RangeBehavior::Clamp);
+        assert_eq!(
+            Ok(Value::new(360.0, "deg")),
+            fam.clamp_value(Value::new(400.0, "deg"))
+        );
+        assert_eq!(
+            Ok(Value::new(0.0, "tr")),
+            fam.clamp_value(Value::new(-1.0, "tr"))
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("m")),
+            fam.clamp_value(Value::new(1.0, "m"))
+        );
+
+        // Wrapping needs both bounds, so a single one is clamped.
+        let fam = angles(RangeBehavior::Wrap).with_range(None, Some(10.0), RangeBehavior::Wrap);
+        assert_eq!(
+            Ok(Value::new(10.0, "deg")),
+            fam.clamp_value(Value::new(400.0, "deg"))
+        );
+        assert_eq!(
+            Ok(Value::new(-400.0, "deg")),
+            fam.clamp_value(Value::new(-400.0, "deg"))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn value_clamp_to_family_range() -> Result {
+        assert_eq!(
+            Value::new(0.0, "k"),
+            Value::new(-1.0, "k").clamp_to_family_range()?
+        );
+        assert_eq!(
+            Value::new(-273.15, "c"),
+            Value::new(-300.0, "c").clamp_to_family_range()?
+        );
+        assert_eq!(
+            Value::new(20.0, "c"),
+            Value::new(20.0, "c").clamp_to_family_range()?
+        );
+        assert_eq!(
+            Value::new(-5.0, "km"),
+            Value::new(-5.0, "km").clamp_to_family_range()?
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            Value::new(1.0, "xyz").clamp_to_family_range()
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    fn family_debug() {
+        let fam = Family::new(
+            "test",
+            "m",
+            vec![
+                Unit::new(vec!["meter"], "m", 1.0, 0.0),
+                Unit::new(vec!["kilometer"], "km", 1000.0, 0.0),
+            ],
+        )
+        .with_description("distances");
+
+        let debug = format!("{:?}", fam);
+        assert_eq!(
+            r#"Family { id: "test", base_unit: "m", units: ["m", "km"] }"#,
+            debug
+        );
+        assert!(!debug.contains("ratio"));
+        assert!(!debug.contains("1000"));
+    }
+
+    #[test]
+    fn family_extend() {
+        let mut fam = Family::new(
+            "test",
+            "m",
+            vec![Unit::new(vec!["meter", "meters"], "m", 1.0, 0.0)],
+        );
+        assert!(!fam.can_convert("km"));
+
+        let prefixes = [
+            ("kilo", "k", 1e3),
+            ("centi", "c", 1e-2),
+            ("milli", "m", 1e-3),
+        ];
+        fam.extend(prefixes.iter().map(|(name, prefix, ratio)| {
+            let name = format!("{}meter", name);
+            Unit::new(vec![&name], &format!("{}m", prefix), *ratio, 0.0)
+        }));
+
+        assert_eq!(4, fam.len());
+        assert!(fam.can_convert("km"));
+        assert!(fam.can_convert("centimeter"));
+        assert_eq!(Some(&fam.units[3]), fam.unit_by_symbol("MM"));
+        assert_eq!(
+            Ok(Value::new(2500.0, "m")),
+            fam.convert(Value::new(2.5, "km"), "m")
+        );
+
+        // Existing units take precedence over clashing ones.
+        fam.extend(vec![Unit::new(vec!["metre"], "m", 2.0, 0.0)]);
+        assert_eq!(Some(&fam.units[0]), fam.unit_by_symbol("m"));
+        assert_eq!(Some(&fam.units[4]), fam.unit_by_symbol("metre"));
+    }
+
+    #[test]
+    fn family_convert_overflow() {
+        let fam = Family::new(
+            "test",
+            "m",
+            vec![
+                Unit::new(vec!["meter", "meters"], "m", 1.0, 0.0),
+                Unit::new(vec!["nanometer", "nanometers"], "nm", 1e-9, 0.0),
+                Unit::new(vec!["parsec", "parsecs"], "pc", 3.0857e16, 0.0),
+            ],
+        );
+
+        assert_eq!(
+            Err(ConversionError::Overflow {
+                from: Value::new(f64::MAX, "pc"),
+                to_unit: "nm".into(),
+            }),
+            fam.convert(Value::new(f64::MAX, "pc"), "nm")
+        );
+
+        for qty in [f64::INFINITY, f64::NAN] {
+            assert!(matches!(
+                fam.convert(Value::new(qty, "m"), "m"),
+                Err(ConversionError::Overflow { .. })
+            ));
+        }
+
+        let fam = fam.with_max_quantity(1e20);
+        assert!(fam.convert(Value::new(1.0, "pc"), "m").is_ok());
+        assert!(matches!(
+            fam.convert(Value::new(1e21, "m"), "m"),
+            Err(ConversionError::Overflow { .. })
+        ));
+        assert!(matches!(
+            fam.convert(Value::new(1.0, "pc"), "nm"),
+            Err(ConversionError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "base unit m isn't the symbol of any unit in test")]
+    fn family_missing_base_unit() {
+        // The base unit must be a symbol, not just a name.
+        Family::new(
+            "test",
+            "m",
+            vec![Unit::new(vec!["m", "meters"], "meter", 1.0, 0.0)],
+        );
+    }
+
+    #[test]
+    fn family_validate() {
+        let fam = Family::new(
+            "test",
+            "m",
+            vec![
+                Unit::new(vec!["meter", "meters"], "m", 1.0, 0.0),
+                Unit::new(vec!["kilometer", "kilometers"], "km", 1000.0, 0.0),
+            ],
+        );
+        assert_eq!(Ok(()), fam.validate());
+
+        let mut zero = Unit::new(vec![], "z", 1.0, 0.0);
+        zero.ratio = 0.0;
+
+        let fam = Family::new_unchecked(
+            "test",
+            "x",
+            vec![
+                Unit::new(vec!["meter", "meters"], "m", 1.0, 0.0),
+                Unit::new(vec!["metre", "meters"], "m", 1.0, 0.0),
+                zero,
+            ],
+        );
+        assert_eq!(
+            Err(vec![
+                FamilyValidationError::BaseUnitNotFound,
+                FamilyValidationError::DuplicateSymbol("m".into()),
+                FamilyValidationError::DuplicateName("meters".into()),
+                FamilyValidationError::ZeroRatio("z".into()),
+                FamilyValidationError::EmptyNames("z".into()),
+            ]),
+            fam.validate()
+        );
+    }
+
     #[test]
     fn family_convert() {
-        let fam = Family {
-            id: "test".into(),
-            base_unit: "k".into(),
-            units: vec![
+        let fam = Family::new(
+            "test",
+            "k",
+            vec![
                 Unit::new(vec!["kelvin", "kelvins"], "K", 1.0, 0.0),
                 Unit::new(vec!["celsius"], "C", 1.0, 273.15),
                 Unit::new(vec!["fahrenheit"], "F", 5.0 / 9.0, 459.67),
             ],
-        };
+        );
 
-        assert_eq!(true, fam.can_convert("k"));
-        assert_eq!(true, fam.can_convert("c"));
-        assert_eq!(true, fam.can_convert("f"));
-        assert_eq!(false, fam.can_convert("r"));
+        assert!(fam.can_convert("k"));
+        assert!(fam.can_convert("c"));
+        assert!(fam.can_convert("f"));
+        assert!(!fam.can_convert("r"));
 
         let cases = [
             ("100k", "100k"),
@@ -269,7 +1996,364 @@ mod tests {
         _ = cases.map(|(given, want)| -> Result {
             let given = given.parse::<Value>()?;
             let want = want.parse::<Value>()?;
-            assert_in_delta(&want, &fam.convert(given.clone(), &want.unit)?)
+            assert_in_delta(
+                &want,
+                &fam.convert(given.clone(), &want.unit)?,
+                DEFAULT_EPSILON,
+            )
         });
+
+        assert_eq!(
+            Err(ConversionError::unknown_unit("r")),
+            fam.convert(Value::new(100.0, "c"), "r")
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("r")),
+            fam.convert(Value::new(100.0, "k"), "r")
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("r")),
+            fam.convert(Value::new(100.0, "r"), "k")
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            fam.convert(Value::new(1.0, "xyz"), "xyz")
+        );
+    }
+
+    #[test]
+    fn value_parts() {
+        let v = Value::new(100.0, "km");
+        assert_eq!((100.0, "km".to_string()), v.clone().into_parts());
+
+        let (qty, unit) = v.clone().into_parts();
+        assert_eq!(v, Value::from_parts(qty, unit));
+        assert_eq!(Value::new(-1.5, "c"), Value::from_parts(-1.5, "c".into()));
+    }
+
+    #[test]
+    fn value_zero_and_one() {
+        assert!(Value::zero("m").is_zero());
+        assert!(Value::new(-0.0, "m").is_zero());
+        assert!(!Value::new(1e-15, "m").is_zero());
+        assert!(!Value::one("m").is_zero());
+        assert_eq!(Value::new(1.0, "m"), Value::one("m"));
+
+        assert_eq!(
+            Value::new(5.0, "m"),
+            Value::zero("m") + Value::new(5.0, "m")
+        );
+    }
+
+    #[test]
+    fn value_default() {
+        #[derive(Default)]
+        struct Reading {
+            value: Value,
+        }
+
+        fn placeholder<T: Default>() -> T {
+            T::default()
+        }
+
+        assert_eq!(Value::new(0.0, ""), Value::default());
+        assert_eq!(Value::default(), placeholder());
+        assert_eq!(Value::default(), Reading::default().value);
+        assert!(crate::convert(Value::default(), "m").is_err());
+    }
+
+    #[test]
+    fn value_try_as_integer() {
+        assert_eq!(Some(1000), Value::new(1000.0, "m").try_as_integer());
+        assert_eq!(Some(-3), Value::new(-3.0, "m").try_as_integer());
+        assert_eq!(Some(0), Value::new(-0.0, "m").try_as_integer());
+        assert_eq!(
+            Some(i64::MIN),
+            Value::new(i64::MIN as f64, "m").try_as_integer()
+        );
+
+        assert_eq!(None, Value::new(1.5, "m").try_as_integer());
+        assert_eq!(None, Value::new(f64::MAX, "m").try_as_integer());
+        assert_eq!(None, Value::new(i64::MAX as f64, "m").try_as_integer());
+        assert_eq!(None, Value::new(f64::INFINITY, "m").try_as_integer());
+        assert_eq!(None, Value::new(f64::NAN, "m").try_as_integer());
+    }
+
+    #[test]
+    fn value_map() {
+        assert_eq!(Value::new(2.0, "m"), Value::new(1.5, "m").map(f64::round));
+        assert_eq!(Value::new(3.0, "c"), Value::new(1.5, "c").map(|q| q * 2.0));
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_and_then() -> Result {
+        let v = Value::new(1.0, "km")
+            .and_then(|v| crate::convert(v, "m"))?
+            .map(|q| q / 10.0);
+        assert_eq!(Value::new(100.0, "m"), v);
+
+        assert!(Value::one("km")
+            .and_then(|v| crate::convert(v, "c"))
+            .is_err());
+
+        Value::ok()
+    }
+
+    #[test]
+    fn value_mul_add() {
+        let v = Value::new(100.0, "c").mul_add(1.8, 32.0);
+        assert_eq!("c", v.unit);
+        assert_in_delta(&Value::new(212.0, "c"), &v, DEFAULT_EPSILON).unwrap();
+
+        // 0.1 isn't exactly representable, so `0.1 * 10.0 - 1.0` isn't quite zero.
+        // A single rounding is at least as close to that as multiplying and adding.
+        let exact = 5.551115123125783e-17;
+        let fused = Value::new(0.1, "m").mul_add(10.0, -1.0).quantity;
+        let separate = Value::new(0.1, "m").map(|q| q * 10.0 - 1.0).quantity;
+        assert!((fused - exact).abs() <= (separate - exact).abs());
+    }
+
+    #[test]
+    fn value_abs_and_clamp() {
+        assert_eq!(Value::new(5.0, "m"), Value::new(-5.0, "m").abs());
+        assert_eq!(Value::new(5.0, "m"), Value::new(5.0, "m").abs());
+
+        let v = Value::new(-5.0, "m");
+        assert_eq!(Value::new(-3.0, "m"), v.clamp(-3.0, 3.0));
+        assert_eq!(Value::new(-5.0, "m"), v.clamp(-10.0, 3.0));
+        assert_eq!(Value::new(3.0, "c"), Value::new(7.5, "c").clamp(-3.0, 3.0));
+    }
+
+    #[test]
+    fn value_sigfig_round() {
+        let round = |qty, n| Value::new(qty, "m").sigfig_round(n).quantity;
+
+        assert_eq!(123000.0, round(123456.0, 3));
+        assert_eq!(1.23, round(1.23456, 3));
+        assert_eq!(10.0, round(9.96, 2));
+        assert_eq!(0.000123, round(0.000123456, 3));
+        assert_eq!(0.5, round(0.4999, 2));
+        assert_eq!(-123000.0, round(-123456.0, 3));
+        assert_eq!(-0.0457, round(-0.045678, 3));
+        assert_eq!(1200.0, round(1200.0, 2));
+        assert_eq!(0.25, round(0.25, 4));
+        assert_eq!(0.0, round(0.0, 3));
+        assert_eq!(42.0, round(42.0, 0));
+        assert!(round(f64::NAN, 3).is_nan());
+
+        assert_eq!("km", Value::new(1.5, "km").sigfig_round(1).unit);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_add() {
+        assert_eq!(
+            Value::new(1.5, "km"),
+            Value::new(1.0, "km") + Value::new(500.0, "m")
+        );
+        assert_eq!(
+            Value::new(1500.0, "m"),
+            Value::new(500.0, "m") + Value::one("km")
+        );
+    }
+
+    #[test]
+    fn value_sum() {
+        let values = vec![
+            Value::new(1.0, "m"),
+            Value::new(2.0, "m"),
+            Value::new(3.0, "m"),
+        ];
+        assert_eq!(Value::new(6.0, "m"), values.into_iter().sum());
+
+        let empty: Vec<Value> = vec![];
+        assert_eq!(Value::new(0.0, ""), empty.into_iter().sum());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_sum_mixed_units() {
+        let values = vec![Value::new(1.0, "km"), Value::new(500.0, "m")];
+        assert_eq!(Value::new(1.5, "km"), values.into_iter().sum());
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    #[should_panic(expected = "cannot add 1.00c to 1.00km")]
+    fn value_sum_incompatible_units() {
+        let values = vec![Value::new(1.0, "km"), Value::new(1.0, "c")];
+        let _: Value = values.into_iter().sum();
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_format_as() {
+        let v = Value::new(1500.0, "m");
+        assert_eq!(Ok("1.50km".to_string()), v.format_as("km", 2));
+        assert_eq!(Ok("1500m".to_string()), v.format_as("m", 0));
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            v.format_as("xyz", 2)
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            Value::new(1.0, "xyz").format_as("m", 2)
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn value_diff() -> Result {
+        let v = Value::new(100.0, "c").diff(&Value::new(212.0, "f"))?;
+        assert_eq!("c", v.unit);
+        assert_in_delta(&Value::new(0.0, "c"), &v, DEFAULT_EPSILON)?;
+
+        let v = Value::new(20.0, "c").diff(&Value::new(300.0, "k"))?;
+        assert_in_delta(&Value::new(-6.85, "c"), &v, DEFAULT_EPSILON)?;
+        assert_eq!(
+            Value::new(-500.0, "m"),
+            Value::new(500.0, "m").diff(&Value::new(1.0, "km"))?
+        );
+
+        assert_eq!(
+            Err(ConversionError::IncompatibleUnits {
+                from: "c".into(),
+                to: "km".into(),
+            }),
+            Value::new(1.0, "km").diff(&Value::new(1.0, "c"))
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            Value::new(1.0, "km").diff(&Value::new(1.0, "xyz"))
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn value_percent_of() -> Result {
+        let pct = |v: Value, r: Value| v.percent_of(&r);
+        assert_eq!(80.0, pct(Value::new(80.0, "m"), Value::new(100.0, "m"))?);
+        assert_eq!(50.0, pct(Value::new(1.0, "km"), Value::new(2000.0, "m"))?);
+        assert_eq!(-25.0, pct(Value::new(-50.0, "cm"), Value::new(2.0, "m"))?);
+
+        // Temperatures are compared in kelvin.
+        let v = pct(Value::new(0.0, "c"), Value::new(546.3, "k"))?;
+        assert_quantity_in_delta(50.0, v, DEFAULT_EPSILON);
+
+        assert_eq!(
+            Err(ConversionError::InvalidArgument(
+                "reference 0.00km is zero".into()
+            )),
+            pct(Value::new(1.0, "m"), Value::new(0.0, "km"))
+        );
+        assert!(matches!(
+            pct(Value::new(1.0, "c"), Value::new(-273.15, "c")),
+            Err(ConversionError::InvalidArgument(_))
+        ));
+        assert_eq!(
+            Err(ConversionError::IncompatibleUnits {
+                from: "c".into(),
+                to: "M".into(),
+            }),
+            pct(Value::new(1.0, "m"), Value::new(1.0, "c"))
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            pct(Value::new(1.0, "xyz"), Value::new(1.0, "m"))
+        );
+
+        Value::ok()
+    }
+
+    #[test]
+    fn value_fmt_duration() {
+        // There's no built-in time family, so register a small one for testing.
+        let registry = crate::UnitRegistry::new();
+        registry
+            .register(Family::new(
+                "Durations",
+                "s",
+                vec![
+                    Unit::new(vec!["second", "seconds"], "s", 1.0, 0.0),
+                    Unit::new(vec!["millisecond", "milliseconds"], "ms", 0.001, 0.0),
+                    Unit::new(vec!["minute", "minutes"], "min", 60.0, 0.0),
+                    Unit::new(vec!["hour", "hours"], "h", 3600.0, 0.0),
+                ],
+            ))
+            .unwrap();
+
+        let fmt_in = |qty, unit| Value::new(qty, unit).fmt_duration_in(&registry);
+        let fmt = |qty, unit| fmt_in(qty, unit).unwrap();
+        assert_eq!("0s", fmt(0.0, "s"));
+        assert_eq!("250ms", fmt(0.25, "s"));
+        assert_eq!("45s", fmt(45.0, "s"));
+        assert_eq!("45s 500ms", fmt(45.5, "s"));
+        assert_eq!("1m 5s", fmt(65.0, "s"));
+        assert_eq!("59m 59s", fmt(3599.0, "s"));
+        assert_eq!("1h", fmt(60.0, "min"));
+        assert_eq!("1h 23m 45s", fmt(5025.0, "s"));
+        assert_eq!("26h 30m", fmt(9.54e7, "ms"));
+        assert_eq!("-1m 30s", fmt(-1.5, "min"));
+
+        assert_eq!(
+            Err(ConversionError::unknown_unit("xyz")),
+            fmt_in(1.0, "xyz")
+        );
+        assert!(fmt_in(f64::INFINITY, "s").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn value_fmt_duration_non_time() {
+        assert!(matches!(
+            Value::new(1.0, "km").fmt_duration(),
+            Err(ConversionError::UnknownUnit { unit, .. }) if unit == "s"
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature"))]
+    fn unit_and_family_from_str() {
+        assert_eq!("km", "km".parse::<Unit>().unwrap().symbol);
+        assert_eq!("km", " Kilometers ".parse::<Unit>().unwrap().symbol);
+        assert_eq!(
+            Err(ParseValueError::new("unknown unit: xyz")),
+            "xyz".parse::<Unit>()
+        );
+
+        assert_eq!("Temperature", "temperature".parse::<Family>().unwrap().id);
+        assert_eq!("Lengths", "LENGTHS".parse::<Family>().unwrap().id);
+        assert!("widgets".parse::<Family>().is_err());
+    }
+
+    #[test]
+    fn value_into_float() {
+        fn total<T: Into<f64>>(values: Vec<T>) -> f64 {
+            values.into_iter().map(Into::into).sum()
+        }
+
+        let v = Value::new(42.0, "m");
+        assert_eq!(42.0, f64::from(&v));
+        assert_eq!(42.0, f64::from(v.clone()));
+        assert_eq!(42.0_f32, f32::from(v.clone()));
+        assert_eq!(84.0, total(vec![v.clone(), v]));
+    }
+
+    #[test]
+    fn conversion_result() {
+        let res = ConversionResult {
+            from: Value::new(100.0, "c"),
+            to: Value::new(212.0, "f"),
+        };
+        assert_eq!("100.00c = 212.00f", res.to_string());
+
+        let res = ConversionResult {
+            from: Value::new(10.0, "km"),
+            to: Value::new(10000.0, "m"),
+        };
+        assert_eq!(1000.0, res.factor());
     }
 }
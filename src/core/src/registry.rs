@@ -0,0 +1,532 @@
+use super::measurement::Measurement;
+use super::prelude::{ConversionError, Family, FamilyValidationError, Unit, Value};
+use super::suggest::{self, Suggestions};
+use std::fmt;
+use std::result;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+/// A thread-safe collection of measurements (usually families) that conversions
+/// are performed with.
+///
+/// Cloning a registry is cheap, and clones share the same measurements, so one
+/// registered through one clone is visible to all of them. Registered
+/// measurements live for the rest of the program, which allows lookups to
+/// return `'static` references that don't hold the lock.
+#[derive(Clone, Default)]
+pub struct UnitRegistry {
+    measurements: Arc<RwLock<Vec<&'static dyn Measurement>>>,
+}
+
+impl UnitRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the global registry used by the top-level functions (e.g.
+    /// `core::convert`), which contains all of the built-in families.
+    pub fn global() -> &'static UnitRegistry {
+        &crate::FAMILIES
+    }
+
+    /// Adds the family to the registry. This acquires a write lock, blocking
+    /// until any lookups in progress have finished.
+    ///
+    /// Returns an error if a family with the same id has already been registered
+    /// or if `Family::validate` finds any problems with the family (e.g. its base
+    /// unit isn't the symbol of one of its units).
+    pub fn register(&self, family: Family) -> result::Result<(), RegistryError> {
+        if family.units().is_empty() {
+            return Err(RegistryError::InvalidFamily(format!(
+                "{} has no units",
+                family.id
+            )));
+        }
+
+        family.validate()?;
+        self.register_measurement(family)
+    }
+
+    /// Adds a custom measurement to the registry. This acquires a write lock,
+    /// blocking until any lookups in progress have finished.
+    ///
+    /// Returns an error if a measurement with the same id has already been
+    /// registered.
+    pub fn register_measurement<M>(&self, measurement: M) -> result::Result<(), RegistryError>
+    where
+        M: Measurement + 'static,
+    {
+        let mut all = self.measurements.write().unwrap_or_else(|e| e.into_inner());
+
+        if all.iter().any(|m| m.id() == measurement.id()) {
+            return Err(RegistryError::DuplicateId(measurement.id().into()));
+        }
+
+        all.push(Box::leak(Box::new(measurement)));
+        Ok(())
+    }
+
+    /// Adds the unit to the registered family with the given id (case
+    /// insensitive). This acquires a write lock, blocking until any lookups in
+    /// progress have finished.
+    ///
+    /// Families can't be changed once registered, so the family is replaced by a
+    /// copy that includes the unit. References to the old family (e.g. from
+    /// `find`) won't see the new unit. Like every registered family, the copy is
+    /// leaked (via `Box::leak`) so lookups can return `'static` references, which
+    /// means each call leaks a full copy of the family. Prefer registering a
+    /// family with all of its units where possible.
+    ///
+    /// Returns an error if there's no such family, it already has a unit with the
+    /// same symbol or name, or `Family::validate` finds a problem with the new
+    /// unit (e.g. a zero ratio).
+    pub fn register_unit(&self, family_id: &str, unit: Unit) -> result::Result<(), RegistryError> {
+        let mut all = self.measurements.write().unwrap_or_else(|e| e.into_inner());
+
+        let (i, family) = all
+            .iter()
+            .enumerate()
+            .find_map(|(i, m)| {
+                m.family()
+                    .filter(|f| f.id.eq_ignore_ascii_case(family_id))
+                    .map(|f| (i, f))
+            })
+            .ok_or_else(|| RegistryError::FamilyNotFound(family_id.into()))?;
+
+        if let Some(key) = unit.keys().find(|k| family.has_key(k)) {
+            return Err(RegistryError::DuplicateSymbol(key));
+        }
+
+        let mut family = family.clone();
+        family.push_unit(unit);
+        family.validate()?;
+        all[i] = Box::leak(Box::new(family));
+        Ok(())
+    }
+
+    /// Returns a snapshot of the registered measurements, in registration order.
+    pub fn measurements(&self) -> Vec<&'static dyn Measurement> {
+        self.read().clone()
+    }
+
+    /// Returns a snapshot of the registered families, in registration order.
+    /// Measurements that aren't backed by a family are skipped.
+    pub fn families(&self) -> Vec<&'static Family> {
+        self.read().iter().filter_map(|m| m.family()).collect()
+    }
+
+    /// Returns the number of registered measurements.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns true when nothing has been registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the first registered family containing the given unit.
+    pub fn find(&self, unit: &str) -> Option<&'static Family> {
+        self.read()
+            .iter()
+            .filter_map(|m| m.family())
+            .find(|f| f.can_convert(unit))
+    }
+
+    /// Returns the registered family with the given id (case insensitive).
+    pub fn find_by_id(&self, id: &str) -> Option<&'static Family> {
+        self.read()
+            .iter()
+            .filter_map(|m| m.family())
+            .find(|f| f.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Returns the first registered measurement containing the given unit.
+    pub fn find_measurement(&self, unit: &str) -> Option<&'static dyn Measurement> {
+        self.read().iter().copied().find(|m| m.can_convert(unit))
+    }
+
+    /// Converts the value into the given unit using the measurement that
+    /// contains it.
+    ///
+    /// Unknown units are reported with suggestions from this registry, and units
+    /// it knows of from another family as `ConversionError::IncompatibleUnits`.
+    pub fn convert(&self, v: Value, to_unit: &str) -> result::Result<Value, ConversionError> {
+        let from = v.unit.clone();
+
+        self.find_measurement(&v.unit)
+            .ok_or_else(|| self.unknown_unit(&v.unit))?
+            .convert(v, to_unit)
+            .map_err(|err| match err {
+                ConversionError::UnknownUnit { unit, .. } if self.find(&unit).is_some() => {
+                    ConversionError::IncompatibleUnits { from, to: unit }
+                }
+                ConversionError::UnknownUnit { unit, .. } => self.unknown_unit(&unit),
+                err => err,
+            })
+    }
+
+    /// Returns the units of the registered families whose symbol or names are
+    /// closest to the given (unknown) unit, best match first.
+    pub fn suggest_unit(&self, unit: &str) -> Vec<&'static Unit> {
+        let all = self.read();
+        let units = all
+            .iter()
+            .filter_map(|m| m.family())
+            .flat_map(|f| f.units().iter());
+
+        suggest::similar_units(unit, units)
+    }
+
+    /// Acquires a read lock on the measurements. Lookups iterate under the lock
+    /// rather than copying the measurements first.
+    fn read(&self) -> RwLockReadGuard<'_, Vec<&'static dyn Measurement>> {
+        self.measurements.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns an `UnknownUnit` error for the unit that suggests similar units
+    /// from this registry (when there are any). The suggestions aren't searched
+    /// for until the error is displayed.
+    pub fn unknown_unit(&self, unit: &str) -> ConversionError {
+        ConversionError::UnknownUnit {
+            unit: unit.into(),
+            suggestions: Suggestions::from_registry(self),
+        }
+    }
+}
+
+/// Implements fmt::Debug for UnitRegistry, listing the registered ids.
+impl fmt::Debug for UnitRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ids: Vec<String> = self.measurements().iter().map(|m| m.id().into()).collect();
+        f.debug_struct("UnitRegistry").field("ids", &ids).finish()
+    }
+}
+
+/// A custom error used to signify errors while registering families.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A family with the same id was already registered.
+    DuplicateId(String),
+    /// The family can't be used for conversions.
+    InvalidFamily(String),
+    /// No family with the id has been registered.
+    FamilyNotFound(String),
+    /// The family already has a unit with the symbol (or name).
+    DuplicateSymbol(String),
+}
+
+/// Converts the problems found by `Family::validate` into an `InvalidFamily`
+/// error.
+impl From<Vec<FamilyValidationError>> for RegistryError {
+    fn from(errors: Vec<FamilyValidationError>) -> Self {
+        let reasons: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        Self::InvalidFamily(reasons.join(", "))
+    }
+}
+
+/// Marks RegistryError as an Error.
+impl std::error::Error for RegistryError {}
+
+/// Implements fmt::Display for RegistryError.
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DuplicateId(id) => write!(f, "family already registered: {}", id),
+            Self::InvalidFamily(reason) => write!(f, "invalid family: {}", reason),
+            Self::FamilyNotFound(id) => write!(f, "unknown family: {}", id),
+            Self::DuplicateSymbol(sym) => write!(f, "unit already registered: {}", sym),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Unit;
+    use std::thread;
+
+    fn widgets() -> Family {
+        Family::new(
+            "Widgets",
+            "w",
+            vec![
+                Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0),
+                Unit::new(vec!["kilowidget", "kilowidgets"], "kw", 1000.0, 0.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn register() {
+        let registry = UnitRegistry::new();
+        assert!(registry.is_empty());
+        assert!(registry.families().is_empty());
+        assert!(registry.convert(Value::new(1.0, "kw"), "w").is_err());
+
+        let clone = registry.clone();
+        clone.register(widgets()).unwrap();
+
+        assert_eq!(1, registry.len());
+        assert_eq!(1, registry.families().len());
+        assert_eq!(
+            Value::new(2000.0, "w"),
+            registry.convert(Value::new(2.0, "kw"), "w").unwrap()
+        );
+    }
+
+    #[test]
+    fn find_by_id() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+        registry.register_measurement(Decibels).unwrap();
+
+        assert_eq!(
+            Some("Widgets"),
+            registry.find_by_id("widgets").map(|f| f.id.as_str())
+        );
+        assert_eq!(None, registry.find_by_id("Decibels"));
+        assert_eq!(None, registry.find_by_id("Gadgets"));
+    }
+
+    #[test]
+    fn unknown_unit_suggestions() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+
+        let err = registry
+            .convert(Value::new(1.0, "w"), "kilowidgit")
+            .unwrap_err();
+        assert_eq!(
+            "unknown unit 'kilowidgit'; did you mean: 'kilowidget', 'kw'?",
+            err.to_string()
+        );
+        assert_eq!(
+            "unknown unit: xyz",
+            registry.unknown_unit("xyz").to_string()
+        );
+        assert!(UnitRegistry::new().suggest_unit("widget").is_empty());
+    }
+
+    #[test]
+    fn convert_incompatible_units() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+        registry
+            .register(Family::new(
+                "Gadgets",
+                "g",
+                vec![Unit::new(vec!["gadget", "gadgets"], "g", 1.0, 0.0)],
+            ))
+            .unwrap();
+
+        let err = registry.convert(Value::new(1.0, "kw"), "g").unwrap_err();
+        assert_eq!(
+            ConversionError::IncompatibleUnits {
+                from: "kw".into(),
+                to: "g".into(),
+            },
+            err
+        );
+        assert_eq!(
+            "can't convert kw to g: they measure different things",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn unknown_unit_suggestions_are_lazy() {
+        let registry = UnitRegistry::new();
+        let err = registry.unknown_unit("kilowidgit");
+        assert_eq!(None, err.suggestion());
+
+        registry.register(widgets()).unwrap();
+        assert_eq!(Some("'kilowidget', 'kw'".into()), err.suggestion());
+        assert_eq!(ConversionError::unknown_unit("kilowidgit"), err);
+    }
+
+    #[test]
+    fn register_unit() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+        registry.register_measurement(Decibels).unwrap();
+
+        let megawidget = Unit::new(vec!["megawidget", "megawidgets"], "MW", 1e6, 0.0);
+        registry.register_unit("widgets", megawidget).unwrap();
+
+        assert_eq!(2, registry.len());
+        assert_eq!(3, registry.families()[0].units().len());
+        assert_eq!(
+            Value::new(2000.0, "kw"),
+            registry
+                .convert(Value::new(2.0, "megawidgets"), "kw")
+                .unwrap()
+        );
+
+        let dupe = |names, sym| registry.register_unit("Widgets", Unit::new(names, sym, 2.0, 0.0));
+        assert_eq!(
+            Err(RegistryError::DuplicateSymbol("kw".into())),
+            dupe(vec!["kw2"], "kw")
+        );
+        assert_eq!(
+            Err(RegistryError::DuplicateSymbol("widget".into())),
+            dupe(vec!["widget"], "w2")
+        );
+        assert_eq!(
+            Err(RegistryError::FamilyNotFound("Decibels".into())),
+            registry.register_unit("Decibels", Unit::new(vec!["bel"], "b", 10.0, 0.0))
+        );
+
+        let mut invalid = Unit::new(vec!["gigawidget"], "gw", 1e9, 0.0);
+        invalid.ratio = 0.0;
+        assert_eq!(
+            Err(RegistryError::InvalidFamily(
+                "zero ratio for unit: gw".into()
+            )),
+            registry.register_unit("widgets", invalid)
+        );
+        assert_eq!(3, registry.families()[0].units().len());
+    }
+
+    #[test]
+    fn register_errors() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+        assert_eq!(
+            Err(RegistryError::DuplicateId("Widgets".into())),
+            registry.register(widgets())
+        );
+
+        let fam = Family::new_unchecked("Gadgets", "g", widgets().into_units());
+        assert_eq!(
+            Err(RegistryError::InvalidFamily("base unit not found".into())),
+            registry.register(fam)
+        );
+
+        // The base unit must be a symbol, not just one of the names.
+        let fam = Family::new_unchecked("Gadgets", "widget", widgets().into_units());
+        assert_eq!(
+            Err(RegistryError::InvalidFamily("base unit not found".into())),
+            registry.register(fam)
+        );
+
+        let mut units = widgets().into_units();
+        units.push(Unit::new(vec!["widget"], "wd", 1.0, 0.0));
+        assert_eq!(
+            Err(RegistryError::InvalidFamily(
+                "duplicate name: widget".into()
+            )),
+            registry.register(Family::new_unchecked("Gadgets", "w", units))
+        );
+
+        let fam = Family::new_unchecked("Empty", "w", vec![]);
+        assert!(matches!(
+            registry.register(fam),
+            Err(RegistryError::InvalidFamily(_))
+        ));
+
+        assert_eq!(1, registry.families().len());
+    }
+
+    /// Converts between decibels and power ratios, which a Family can't express.
+    struct Decibels;
+
+    impl Measurement for Decibels {
+        fn id(&self) -> &str {
+            "Decibels"
+        }
+
+        fn can_convert(&self, unit: &str) -> bool {
+            unit == "db" || unit == "ratio"
+        }
+
+        fn convert(&self, v: Value, to_unit: &str) -> result::Result<Value, ConversionError> {
+            match (v.unit.as_str(), to_unit) {
+                (from, to) if from == to => Ok(v),
+                ("db", "ratio") => Ok(Value::new(10f64.powf(v.quantity / 10.0), to_unit)),
+                ("ratio", "db") => Ok(Value::new(10.0 * v.quantity.log10(), to_unit)),
+                _ => Err(ConversionError::unknown_unit(to_unit)),
+            }
+        }
+    }
+
+    #[test]
+    fn register_measurement() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+        registry.register_measurement(Decibels).unwrap();
+
+        assert_eq!(2, registry.len());
+        assert_eq!(1, registry.families().len());
+        assert_eq!(
+            vec!["Widgets", "Decibels"],
+            registry
+                .measurements()
+                .iter()
+                .map(|m| m.id())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            Value::new(100.0, "ratio"),
+            registry.convert(Value::new(20.0, "db"), "ratio").unwrap()
+        );
+        assert_eq!(
+            Value::new(30.0, "db"),
+            registry.convert(Value::new(1000.0, "ratio"), "db").unwrap()
+        );
+        assert!(registry.convert(Value::new(1.0, "db"), "w").is_err());
+        assert!(registry.find("db").is_none());
+        assert!(registry.find_measurement("db").is_some());
+
+        assert_eq!(
+            Err(RegistryError::DuplicateId("Decibels".into())),
+            registry.register_measurement(Decibels)
+        );
+    }
+
+    #[test]
+    fn global() {
+        let ids: Vec<&str> = UnitRegistry::global()
+            .families()
+            .iter()
+            .map(|f| f.id.as_str())
+            .collect();
+
+        let builtin = [
+            cfg!(feature = "length").then_some("Lengths"),
+            cfg!(feature = "temperature").then_some("Temperature"),
+            cfg!(feature = "voltage").then_some("Voltage"),
+        ];
+        for id in builtin.into_iter().flatten() {
+            assert!(ids.contains(&id), "{}", id);
+        }
+    }
+
+    #[test]
+    fn concurrent_access() {
+        let registry = UnitRegistry::new();
+        registry.register(widgets()).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let registry = registry.clone();
+                thread::spawn(move || {
+                    if i % 2 == 0 {
+                        let mut fam = widgets();
+                        fam.id = format!("Widgets {}", i);
+                        registry.register(fam).unwrap();
+                    }
+
+                    for _ in 0..100 {
+                        let v = registry.convert(Value::new(1.0, "kw"), "w").unwrap();
+                        assert_eq!(Value::new(1000.0, "w"), v);
+                    }
+                })
+            })
+            .collect();
+
+        handles.into_iter().for_each(|h| h.join().unwrap());
+        assert_eq!(5, registry.families().len());
+    }
+}
@@ -0,0 +1,145 @@
+use super::prelude::Unit;
+use super::registry::UnitRegistry;
+use std::fmt;
+
+/// The most suggestions returned by `suggest_unit`.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Returns the known units whose symbol or names are closest to the given
+/// (unknown) unit, best match first.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let units = core::suggest_unit("kilometre");
+/// assert_eq!("km", units[0].symbol);
+/// # }
+/// ```
+pub fn suggest_unit(unit: &str) -> Vec<&'static Unit> {
+    UnitRegistry::global().suggest_unit(unit)
+}
+
+/// The registry an `UnknownUnit` error suggests similar units from. Nothing is
+/// searched until the suggestions are needed (see `ConversionError::suggestion`),
+/// so creating the error only costs a clone of the registry handle.
+///
+/// Suggestions are ignored when comparing errors, so errors for the same unit are
+/// equal no matter which registry reported them.
+#[derive(Clone, Default)]
+pub struct Suggestions(Option<UnitRegistry>);
+
+impl Suggestions {
+    /// Suggests units from the given registry.
+    pub(crate) fn from_registry(registry: &UnitRegistry) -> Self {
+        Self(Some(registry.clone()))
+    }
+
+    /// Returns the units similar to the given one (e.g. `'kilometer', 'km'`), or
+    /// None when there aren't any.
+    pub(crate) fn describe(&self, unit: &str) -> Option<String> {
+        let suggestions: Vec<String> = self
+            .0
+            .as_ref()?
+            .suggest_unit(unit)
+            .iter()
+            .map(|u| format!("'{}', '{}'", u.names[0], u.symbol))
+            .collect();
+
+        (!suggestions.is_empty()).then(|| suggestions.join(", "))
+    }
+}
+
+/// Implements PartialEq for Suggestions. All suggestions are equal (see above).
+impl PartialEq for Suggestions {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Implements fmt::Debug for Suggestions, showing the registry they come from.
+impl fmt::Debug for Suggestions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Suggestions").field(&self.0).finish()
+    }
+}
+
+/// Returns the units whose symbol or names are closest to the given unit, best
+/// match first.
+pub(crate) fn similar_units<'a>(
+    unit: &str,
+    units: impl Iterator<Item = &'a Unit>,
+) -> Vec<&'a Unit> {
+    let unit = unit.to_lowercase();
+    // Allow roughly one typo for every three characters.
+    let max_distance = (unit.chars().count() / 3).max(1);
+
+    let mut found: Vec<(usize, &Unit)> = units
+        .filter_map(|u| {
+            u.names
+                .iter()
+                .chain(Some(&u.symbol))
+                .map(|key| levenshtein(&unit, key))
+                .min()
+                .filter(|d| *d <= max_distance)
+                .map(|d| (d, u))
+        })
+        .collect();
+
+    found.sort_by_key(|(d, _)| *d);
+    found
+        .into_iter()
+        .map(|(_, u)| u)
+        .take(MAX_SUGGESTIONS)
+        .collect()
+}
+
+/// Returns the number of single character edits needed to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn levenshtein() {
+        let cases = [
+            ("", "", 0),
+            ("km", "km", 0),
+            ("", "km", 2),
+            ("kilometre", "kilometer", 2),
+            ("kitten", "sitting", 3),
+        ];
+
+        for (a, b, want) in cases {
+            assert_eq!(want, super::levenshtein(a, b), "{} -> {}", a, b);
+            assert_eq!(want, super::levenshtein(b, a), "{} -> {}", b, a);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn suggest_unit() {
+        let units = super::suggest_unit("Kilometre");
+        assert_eq!("km", units[0].symbol);
+
+        let units = super::suggest_unit("fot");
+        assert_eq!("ft", units[0].symbol);
+
+        assert!(super::suggest_unit("xyzzy").is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+use super::prelude::{ConversionError, Family};
+use super::registry::UnitRegistry;
+use std::result;
+
+/// The output formats supported by `family_conversion_table`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    Markdown,
+}
+
+/// Returns a table of the conversion factors between every pair of units in the
+/// family. Each cell is the factor from the row's unit to the column's unit, or
+/// `N/A` when the conversion isn't linear (e.g. celsius to fahrenheit).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "temperature")] {
+/// use core::TableFormat;
+///
+/// let table = core::family_conversion_table("Temperature", TableFormat::Csv).unwrap();
+/// let rows: Vec<&str> = table.lines().collect();
+/// assert_eq!(",k,c,f,r", rows[0]);
+/// assert_eq!("k,1.00,N/A,N/A,1.80", rows[1]);
+/// # }
+/// ```
+pub fn family_conversion_table(
+    family_id: &str,
+    format: TableFormat,
+) -> result::Result<String, ConversionError> {
+    let fam = UnitRegistry::global()
+        .find_by_id(family_id)
+        .ok_or_else(|| ConversionError::UnknownFamily(family_id.into()))?;
+
+    let symbols: Vec<&str> = fam.units().iter().map(|u| u.symbol.as_str()).collect();
+    let mut rows = vec![row(format, "", &symbols)];
+    if format == TableFormat::Markdown {
+        rows.push(format!("|{}", "---|".repeat(symbols.len() + 1)));
+    }
+
+    for from in &symbols {
+        let cells: Vec<String> = symbols.iter().map(|to| cell(fam, from, to)).collect();
+        let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+        rows.push(row(format, from, &cells));
+    }
+
+    Ok(rows.join("\n"))
+}
+
+/// Returns the symbols of the family's units along with the matrix of conversion
+/// factors between them, where `matrix[i][j]` is the factor from unit `i` to
+/// unit `j`. Families with any non-linear units (e.g. temperatures) return
+/// `ConversionError::NonLinearConversion`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "length")] {
+/// let (symbols, matrix) = core::conversion_matrix("Lengths").unwrap();
+/// assert_eq!(("m", "km"), (symbols[0].as_str(), symbols[3].as_str()));
+/// assert_eq!(0.001, matrix[0][3]);
+/// # }
+/// ```
+pub fn conversion_matrix(
+    family_id: &str,
+) -> result::Result<(Vec<String>, Vec<Vec<f64>>), ConversionError> {
+    let fam = UnitRegistry::global()
+        .find_by_id(family_id)
+        .ok_or_else(|| ConversionError::UnknownFamily(family_id.into()))?;
+
+    let matrix = fam
+        .units()
+        .iter()
+        .map(|from| {
+            fam.units()
+                .iter()
+                .map(|to| from.conversion_factor_to(to))
+                .collect::<Option<Vec<_>>>()
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ConversionError::NonLinearConversion)?;
+
+    let symbols = fam.units().iter().map(|u| u.symbol.clone()).collect();
+    Ok((symbols, matrix))
+}
+
+fn row(format: TableFormat, header: &str, cells: &[&str]) -> String {
+    let mut all = vec![header];
+    all.extend_from_slice(cells);
+
+    match format {
+        TableFormat::Csv => all.join(","),
+        TableFormat::Tsv => all.join("\t"),
+        TableFormat::Markdown => format!("| {} |", all.join(" | ")),
+    }
+}
+
+fn cell(fam: &Family, from: &str, to: &str) -> String {
+    if from == to {
+        return format!("{:.2}", 1.0);
+    }
+
+    match fam.conversion_factor(from, to) {
+        // Very small or large factors would round away in fixed notation.
+        Ok(f) if f.abs() < 0.01 || f.abs() >= 1e6 => format!("{:.2e}", f),
+        Ok(f) => format!("{:.2}", f),
+        Err(_) => "N/A".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn csv() {
+        let table = family_conversion_table("Temperature", TableFormat::Csv).unwrap();
+        let rows: Vec<Vec<&str>> = table.lines().map(|l| l.split(',').collect()).collect();
+
+        assert_eq!(5, rows.len());
+        assert!(rows.iter().all(|r| r.len() == 5));
+        assert_eq!(vec!["", "k", "c", "f", "r"], rows[0]);
+        (1..5).for_each(|i| assert_eq!("1.00", rows[i][i]));
+
+        assert_eq!(vec!["k", "1.00", "N/A", "N/A", "1.80"], rows[1]);
+        assert_eq!("0.56", rows[4][1]);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn tsv_and_markdown() {
+        let table = family_conversion_table("lengths", TableFormat::Tsv).unwrap();
+        assert!(table.starts_with("\tm\tcm\tmm\tkm\t"));
+        assert!(table.contains("\nkm\t1000.00\t100000.00\t1.00e6\t1.00\t"));
+
+        let table = family_conversion_table("Lengths", TableFormat::Markdown).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("|  | m | cm | mm | km |"));
+        assert_eq!("|---|---|---|---|---|---|---|---|---|---|", lines[1]);
+        assert!(lines[2].starts_with("| m | 1.00 | 100.00 | 1000.00 | 1.00e-3 |"));
+    }
+
+    #[test]
+    fn unknown_family() {
+        assert_eq!(
+            Err(ConversionError::UnknownFamily("widgets".into())),
+            family_conversion_table("widgets", TableFormat::Csv)
+        );
+        assert_eq!(
+            Err(ConversionError::UnknownFamily("widgets".into())),
+            conversion_matrix("widgets")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn matrix() {
+        let (symbols, matrix) = conversion_matrix("lengths").unwrap();
+        assert_eq!(9, symbols.len());
+        assert_eq!(9, matrix.len());
+
+        for i in 0..9 {
+            assert_eq!(9, matrix[i].len());
+            assert_eq!(1.0, matrix[i][i]);
+            for j in 0..9 {
+                let product = matrix[i][j] * matrix[j][i];
+                assert!(
+                    (product - 1.0).abs() < 1e-12,
+                    "{} <-> {}",
+                    symbols[i],
+                    symbols[j]
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn matrix_non_linear() {
+        assert_eq!(
+            Err(ConversionError::NonLinearConversion),
+            conversion_matrix("Temperature")
+        );
+    }
+}
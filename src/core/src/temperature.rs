@@ -1,24 +1,45 @@
-use super::prelude::{Family, Unit};
+use super::prelude::{Family, PhysicalConstraint, RangeBehavior, Unit};
 
 /// Returns a family that can convert between temperature units.
 pub fn family() -> Family {
-    Family {
-        id: "Temperature".into(),
-        base_unit: "K".into(),
-        units: vec![
-            Unit::new(vec!["kelvin", "kelvins"], "K", 1.0, 0.0),
-            Unit::new(vec!["celsius"], "C", 1.0, 273.15),
-            Unit::new(vec!["fahrenheit"], "F", 5.0 / 9.0, 459.67),
-            Unit::new(vec!["rankine"], "R", 5.0 / 9.0, 0.0),
+    Family::new(
+        "Temperature",
+        "K",
+        vec![
+            Unit::new(vec!["kelvin", "kelvins"], "K", 1.0, 0.0)
+                .with_system("metric")
+                .with_description("absolute thermodynamic temperature scale"),
+            Unit::new(vec!["celsius"], "C", 1.0, 273.15)
+                .with_system("metric")
+                .with_description("water freezes at 0 and boils at 100"),
+            Unit::new(vec!["fahrenheit"], "F", 5.0 / 9.0, 459.67)
+                .with_system("imperial")
+                .with_description("water freezes at 32 and boils at 212"),
+            Unit::new(vec!["rankine"], "R", 5.0 / 9.0, 0.0)
+                .with_system("imperial")
+                .with_description("absolute scale using fahrenheit degrees"),
         ],
-    }
+    )
+    .with_description("how hot or cold something is")
+    // Nothing is colder than absolute zero.
+    .with_constraint(PhysicalConstraint::NonNegative)
+    .with_range(Some(0.0), None, RangeBehavior::Clamp)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prelude::{ConversionError, Value};
     use crate::testutil::assertions::*;
 
+    /// Temperatures are compared to a millionth of a degree.
+    const TEMPERATURE_EPSILON: f64 = 1e-6;
+
+    #[test]
+    fn invariants() {
+        assert_family_invariants(&family());
+    }
+
     #[test]
     fn convert() {
         let fam = family();
@@ -33,4 +54,101 @@ mod tests {
         assert_to_base_unit(&fam, &cases);
         assert_from_base_unit(&fam, &cases);
     }
+
+    #[test]
+    fn common_values() {
+        let fam = family();
+        assert_convert_eq(&fam, "100c", "f", 212.0);
+        assert_convert_eq(&fam, "32f", "c", 0.0);
+        assert_convert_eq(&fam, "-40c", "f", -40.0);
+        assert_convert_eq(&fam, "0k", "c", -273.15);
+        assert_convert_eq(&fam, "0c", "r", 491.67);
+    }
+
+    #[test]
+    fn absolute_zero() {
+        let fam = family();
+        assert_eq!(
+            Err(ConversionError::PhysicalConstraintViolated),
+            fam.convert(Value::new(-300.0, "k"), "c")
+        );
+        assert_eq!(
+            Err(ConversionError::PhysicalConstraintViolated),
+            fam.convert(Value::new(-500.0, "f"), "c")
+        );
+        assert_eq!(
+            Err(ConversionError::PhysicalConstraintViolated),
+            fam.convert(Value::new(-300.0, "k"), "k")
+        );
+
+        let v = fam.convert(Value::new(-100.0, "c"), "k").unwrap();
+        assert_in_delta(&Value::new(173.15, "k"), &v, TEMPERATURE_EPSILON).unwrap();
+    }
+
+    #[test]
+    fn conversion_factor() {
+        let fam = family();
+        assert_eq!(
+            Err(ConversionError::NonLinearConversion),
+            fam.conversion_factor("c", "f")
+        );
+        let factor = fam.conversion_factor("k", "r").unwrap();
+        assert_quantity_in_delta(1.8, factor, TEMPERATURE_EPSILON);
+    }
+
+    #[test]
+    fn display_ratio() {
+        let fam = family();
+        let ratio = |sym| fam.unit_by_symbol(sym).unwrap().display_ratio(&fam);
+
+        assert_eq!("1 k = 1 k", ratio("k"));
+        assert_eq!("k = (c + 273.15) × 1", ratio("c"));
+        assert!(ratio("f").starts_with("k = (f + 459.67) × 0.5555"));
+    }
+
+    #[test]
+    fn unit_conversion_factor_to() {
+        let fam = family();
+        let unit = |sym| fam.unit_by_symbol(sym).unwrap();
+
+        assert_eq!(None, unit("c").conversion_factor_to(unit("f")));
+        assert_eq!(None, unit("k").conversion_factor_to(unit("c")));
+        assert!(unit("k").conversion_factor_to(unit("r")).is_some());
+    }
+
+    #[test]
+    fn range() {
+        let fam = family();
+        let values = fam.range(0.0, 100.0, 5, "c").unwrap();
+        assert_eq!(
+            vec![0.0, 25.0, 50.0, 75.0, 100.0],
+            values.iter().map(|v| v.quantity).collect::<Vec<_>>()
+        );
+        assert!(values.iter().all(|v| v.unit == "c"));
+
+        assert_eq!(
+            vec![Value::new(32.0, "f")],
+            fam.range(32.0, 212.0, 1, "f").unwrap()
+        );
+        assert_eq!(
+            vec![Value::new(10.0, "k"), Value::new(0.0, "k")],
+            fam.range(10.0, 0.0, 2, "k").unwrap()
+        );
+
+        assert_eq!(
+            Err(ConversionError::InvalidArgument(
+                "steps must be at least 1".into()
+            )),
+            fam.range(0.0, 100.0, 0, "c")
+        );
+        assert_eq!(
+            Err(ConversionError::unknown_unit("m")),
+            fam.range(0.0, 100.0, 5, "m")
+        );
+    }
+
+    #[test]
+    fn symmetry() {
+        assert_full_symmetry(&family(), TEMPERATURE_EPSILON);
+    }
 }
@@ -1,54 +1,56 @@
 #[cfg(test)]
+// Most of the helpers are only used by the tests of the built-in families.
+#[cfg_attr(
+    not(any(feature = "length", feature = "temperature", feature = "voltage")),
+    allow(dead_code)
+)]
 pub mod assertions {
-    use crate::prelude::{Family, Result, Value};
+    use crate::prelude::{Family, Result, Unit, Value};
 
-    /// EPSILON defines the value used in assert_in_delta to compare values.
-    /// Two values are "equivalent" if their difference is less than this
-    /// value.
-    const EPSILON: f64 = 0.001;
+    /// DEFAULT_EPSILON defines the value used by the helpers below to compare
+    /// values. Two values are "equivalent" if their difference is less than
+    /// this value.
+    pub const DEFAULT_EPSILON: f64 = 0.001;
 
     /// TestCase defines a unit conversion test case. It's a tuple of two strings,
     /// namely a unit and it's expected value in the base unit for the family.
     pub type TestCase<'a> = (&'a str, &'a str);
 
+    /// Ensures that the family is valid and its base unit is one of its units.
+    pub fn assert_family_invariants(fam: &Family) {
+        assert_eq!(Ok(()), fam.validate_base_unit_exists(), "{}", fam.id);
+        assert_eq!(Ok(()), fam.validate(), "{}", fam.id);
+    }
+
     /// Ensures that converting the given value of each TestCase results in the equivalent
     /// value.
     pub fn assert_identities(fam: &Family, cases: &Vec<TestCase>) {
-        let mut res = cases.iter().map(|(given, _)| -> Result {
-            let given = given.parse::<Value>()?;
-            assert_in_delta(&given, &fam.convert(given.clone(), &given.unit)?)
-        });
-
-        assert_eq!(true, res.all(|r| r.is_ok()));
+        for (given, _) in cases {
+            let v = parse(given);
+            assert_convert_eq(fam, given, &v.unit, v.quantity);
+        }
     }
 
     /// Ensures that each value can be converted from the given unit to the base unit.
     pub fn assert_to_base_unit(fam: &Family, cases: &Vec<TestCase>) {
-        let mut res = cases.iter().map(|(given, want)| -> Result {
-            let given = given.parse::<Value>()?;
-            let want = want.parse::<Value>()?;
-            assert_in_delta(&want, &fam.convert(given.clone(), &want.unit)?)
-        });
-
-        assert_eq!(true, res.all(|r| r.is_ok()));
+        for (given, want) in cases {
+            let want = parse(want);
+            assert_convert_eq(fam, given, &want.unit, want.quantity);
+        }
     }
 
     /// Ensures that each value can be converted from the base unit to the given unit.
     pub fn assert_from_base_unit(fam: &Family, cases: &Vec<TestCase>) {
-        let mut res = cases.iter().map(|(want, given)| -> Result {
-            let given = given.parse::<Value>()?;
-            let want = want.parse::<Value>()?;
-            assert_in_delta(&want, &fam.convert(given.clone(), &want.unit)?)
-        });
-
-        assert_eq!(true, res.all(|r| r.is_ok()));
+        for (want, given) in cases {
+            let want = parse(want);
+            assert_convert_eq(fam, given, &want.unit, want.quantity);
+        }
     }
 
-    /// Ensures that the difference between values is < EPSILON.
-    pub fn assert_in_delta(exp: &Value, got: &Value) -> Result {
-        assert_eq!(
-            true,
-            exp.quantity - got.quantity < EPSILON && got.quantity - exp.quantity < EPSILON,
+    /// Ensures that the difference between values is < epsilon.
+    pub fn assert_in_delta(exp: &Value, got: &Value, epsilon: f64) -> Result {
+        assert!(
+            exp.quantity - got.quantity < epsilon && got.quantity - exp.quantity < epsilon,
             "expected: {}, got: {}",
             exp,
             got,
@@ -56,4 +58,123 @@ pub mod assertions {
 
         Value::ok()
     }
+
+    /// Ensures that the difference between the quantities is < epsilon.
+    pub fn assert_quantity_in_delta(exp: f64, got: f64, epsilon: f64) {
+        assert!(
+            (exp - got).abs() < epsilon,
+            "expected: {}, got: {} (epsilon: {})",
+            exp,
+            got,
+            epsilon,
+        );
+    }
+
+    /// Ensures that converting `from` (e.g. `1km`) into the `to` unit results in
+    /// the expected quantity (within DEFAULT_EPSILON).
+    pub fn assert_convert_eq(fam: &Family, from: &str, to: &str, expected_qty: f64) {
+        let from = parse(from);
+        let got = fam
+            .convert(from.clone(), to)
+            .unwrap_or_else(|e| panic!("failed to convert {} to {}: {}", from, to, e));
+
+        let delta = (expected_qty - got.quantity).abs();
+        assert!(
+            delta < DEFAULT_EPSILON,
+            "{} -> {}: expected {}, got {} (delta: {})",
+            from,
+            to,
+            expected_qty,
+            got.quantity,
+            delta,
+        );
+    }
+
+    fn parse(s: &str) -> Value {
+        s.parse()
+            .unwrap_or_else(|e| panic!("invalid value {}: {}", s, e))
+    }
+
+    /// Ensures that converting each value into the paired unit and back again
+    /// results in the original value (within epsilon).
+    pub fn assert_round_trip<A, B>(fam: &Family, cases: &[(A, B)], epsilon: f64)
+    where
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        for (given, unit) in cases {
+            let given = parse(given.as_ref());
+            let there = fam.convert(given.clone(), unit.as_ref()).unwrap();
+            let back = fam.convert(there.clone(), &given.unit).unwrap();
+
+            assert!(
+                (given.quantity - back.quantity).abs() < epsilon,
+                "{}: {} -> {} -> {} (epsilon: {})",
+                fam.id,
+                given,
+                there,
+                back,
+                epsilon,
+            );
+        }
+    }
+
+    /// Ensures that, for every pair of units (u1, u2) in the family, converting
+    /// a value in u1 into u2 and back again results in the same quantity (within
+    /// epsilon).
+    pub fn assert_full_symmetry(fam: &Family, epsilon: f64) {
+        assert_round_trip(fam, &all_pairs(fam), epsilon);
+    }
+
+    /// Returns a round trip case for every pair of units in the family.
+    pub fn all_pairs(fam: &Family) -> Vec<(String, String)> {
+        fam.units()
+            .iter()
+            .flat_map(|a| {
+                fam.units()
+                    .iter()
+                    .map(move |b| (format!("12.5{}", a.symbol), b.symbol.clone()))
+            })
+            .collect()
+    }
+
+    mod tests {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "expected: 1, got: 1.1")]
+        fn quantity_in_delta() {
+            assert_quantity_in_delta(1.0, 1.0005, 0.001);
+            assert_quantity_in_delta(1.0, 1.1, 0.001);
+        }
+
+        fn widgets() -> Family {
+            Family::new(
+                "Widgets",
+                "w",
+                vec![
+                    Unit::new(vec!["widget"], "w", 1.0, 0.0),
+                    Unit::new(vec!["kilowidget"], "kw", 1000.0, 0.0),
+                ],
+            )
+        }
+
+        #[test]
+        fn convert_eq() {
+            assert_convert_eq(&widgets(), "2kw", "w", 2000.0);
+            assert_convert_eq(&widgets(), "1w", "kw", 0.0015);
+        }
+
+        #[test]
+        #[should_panic(expected = "2.00kw -> w: expected 2001, got 2000 (delta: 1)")]
+        fn convert_eq_mismatch() {
+            assert_convert_eq(&widgets(), "2kw", "w", 2001.0);
+        }
+
+        #[test]
+        #[should_panic(expected = "failed to convert 1.00w to m")]
+        fn convert_eq_unknown_unit() {
+            assert_convert_eq(&widgets(), "1w", "m", 1.0);
+        }
+    }
 }
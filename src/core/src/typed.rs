@@ -0,0 +1,270 @@
+//! Quantities whose unit is part of their type, so mixing up families is caught
+//! at compile time rather than when converting.
+//!
+//! Each unit is a marker type implementing `UnitMarker`, which ties it to the
+//! symbol it's registered with and the dimension (family) it belongs to. Only
+//! quantities of the same dimension can be converted into one another.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "length")]
+//! use core::typed::{Kilometer, Meter, Quantity};
+//!
+//! # #[cfg(feature = "length")]
+//! # fn main() -> Result<(), core::ConversionError> {
+//! let km: Quantity<Kilometer> = Quantity::<Meter>::new(1500.0).convert()?;
+//! assert_eq!(1.5, km.quantity());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "length"))]
+//! # fn main() {}
+//! ```
+//!
+//! Converting between families doesn't compile. The markers are defined here
+//! so the example doesn't depend on which families are enabled:
+//!
+//! ```compile_fail
+//! use core::typed::{Dimension, Quantity, UnitMarker};
+//!
+//! struct Length;
+//! impl Dimension for Length {}
+//!
+//! struct Temperature;
+//! impl Dimension for Temperature {}
+//!
+//! struct Meter;
+//! impl UnitMarker for Meter {
+//!     type Dimension = Length;
+//!     const SYMBOL: &'static str = "m";
+//! }
+//!
+//! struct Celsius;
+//! impl UnitMarker for Celsius {
+//!     type Dimension = Temperature;
+//!     const SYMBOL: &'static str = "c";
+//! }
+//!
+//! let c: Quantity<Celsius> = Quantity::<Meter>::new(1.0).convert().unwrap();
+//! ```
+use super::prelude::{ConversionError, Value};
+use std::fmt;
+use std::marker::PhantomData;
+use std::result;
+
+/// A kind of quantity (e.g. length), corresponding to a unit family.
+pub trait Dimension {}
+
+/// Marks a type as a unit of a particular dimension.
+pub trait UnitMarker {
+    /// The dimension the unit measures.
+    type Dimension: Dimension;
+    /// The symbol of the unit in the registry.
+    const SYMBOL: &'static str;
+}
+
+/// A quantity measured in the unit `U`.
+pub struct Quantity<U: UnitMarker> {
+    quantity: f64,
+    _marker: PhantomData<U>,
+}
+
+impl<U: UnitMarker> Quantity<U> {
+    /// Constructs a new Quantity in the unit `U`.
+    pub fn new(quantity: f64) -> Self {
+        Self {
+            quantity,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the quantity, in the unit `U`.
+    pub fn quantity(&self) -> f64 {
+        self.quantity
+    }
+
+    /// Converts the quantity into another unit of the same dimension.
+    ///
+    /// The conversion goes through the global registry, so it can still fail at
+    /// runtime: with `ConversionError::UnknownUnit` when a marker's `SYMBOL` isn't
+    /// registered (e.g. a custom `UnitMarker`), or when the family rejects the
+    /// value (e.g. a temperature below absolute zero).
+    pub fn convert<V>(self) -> result::Result<Quantity<V>, ConversionError>
+    where
+        V: UnitMarker<Dimension = U::Dimension>,
+    {
+        let v = crate::convert_paired(self.into(), V::SYMBOL)?.to;
+        Ok(Quantity::new(v.quantity))
+    }
+}
+
+// These are implemented by hand since deriving them would require the markers
+// to implement the traits too.
+
+impl<U: UnitMarker> Clone for Quantity<U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U: UnitMarker> Copy for Quantity<U> {}
+
+impl<U: UnitMarker> PartialEq for Quantity<U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.quantity == other.quantity
+    }
+}
+
+impl<U: UnitMarker> fmt::Debug for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Quantity")
+            .field("quantity", &self.quantity)
+            .field("unit", &U::SYMBOL)
+            .finish()
+    }
+}
+
+/// Implements fmt::Display for Quantity, formatting it like a Value.
+impl<U: UnitMarker> fmt::Display for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Value::from(*self).fmt(f)
+    }
+}
+
+/// Implements From<Quantity> for Value.
+impl<U: UnitMarker> From<Quantity<U>> for Value {
+    fn from(q: Quantity<U>) -> Self {
+        Value::new(q.quantity, U::SYMBOL)
+    }
+}
+
+// Only used for the families that are enabled.
+#[cfg_attr(
+    not(any(feature = "length", feature = "temperature", feature = "voltage")),
+    allow(unused_macros)
+)]
+macro_rules! dimension {
+    ($(#[$meta:meta])* $dim:ident { $($unit:ident => $sym:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct $dim;
+
+        impl Dimension for $dim {}
+
+        $(
+            #[doc = concat!("The `", $sym, "` unit of `", stringify!($dim), "`.")]
+            #[derive(Debug)]
+            pub struct $unit;
+
+            impl UnitMarker for $unit {
+                type Dimension = $dim;
+                const SYMBOL: &'static str = $sym;
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "length")]
+dimension!(
+    /// Units of the Lengths family.
+    Length {
+        Meter => "m",
+        Centimeter => "cm",
+        Millimeter => "mm",
+        Kilometer => "km",
+        Foot => "ft",
+        Inch => "in",
+        Yard => "yd",
+        Mile => "mi",
+        NauticalMile => "nmi",
+    }
+);
+
+#[cfg(feature = "temperature")]
+dimension!(
+    /// Units of the Temperature family.
+    Temperature {
+        Kelvin => "k",
+        Celsius => "c",
+        Fahrenheit => "f",
+        Rankine => "r",
+    }
+);
+
+#[cfg(feature = "voltage")]
+dimension!(
+    /// Units of the Voltage family.
+    Voltage {
+        Volt => "v",
+        Microvolt => "uv",
+        Millivolt => "mv",
+        Kilovolt => "kv",
+        Megavolt => "MV",
+        Gigavolt => "gv",
+    }
+);
+
+#[cfg(test)]
+#[cfg(any(feature = "length", feature = "temperature", feature = "voltage"))]
+mod tests {
+    use super::*;
+    use crate::testutil::assertions::*;
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn convert_length() {
+        let km: Quantity<Kilometer> = Quantity::<Meter>::new(1500.0).convert().unwrap();
+        assert_eq!(Quantity::new(1.5), km);
+
+        let ft = Quantity::<Mile>::new(1.0).convert::<Foot>().unwrap();
+        assert_quantity_in_delta(5280.0, ft.quantity(), DEFAULT_EPSILON);
+        assert_eq!("1.50km", km.to_string());
+        assert_eq!(Value::new(1.5, "km"), km.into());
+    }
+
+    #[test]
+    #[cfg(feature = "temperature")]
+    fn convert_temperature() {
+        let f = Quantity::<Celsius>::new(100.0)
+            .convert::<Fahrenheit>()
+            .unwrap();
+        assert_quantity_in_delta(212.0, f.quantity(), DEFAULT_EPSILON);
+
+        assert_eq!(
+            Err(ConversionError::PhysicalConstraintViolated),
+            Quantity::<Kelvin>::new(-1.0).convert::<Celsius>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "voltage")]
+    fn convert_voltage() {
+        let v = Quantity::<Millivolt>::new(1500.0)
+            .convert::<Volt>()
+            .unwrap();
+        assert_quantity_in_delta(1.5, v.quantity(), DEFAULT_EPSILON);
+    }
+
+    #[test]
+    #[cfg(feature = "length")]
+    fn debug() {
+        assert_eq!(
+            "Quantity { quantity: 2.0, unit: \"yd\" }",
+            format!("{:?}", Quantity::<Yard>::new(2.0))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "length", feature = "temperature", feature = "voltage"))]
+    fn markers_are_registered() {
+        fn check<U: UnitMarker>() {
+            assert!(crate::find_unit(U::SYMBOL).is_some(), "{}", U::SYMBOL);
+        }
+
+        check::<NauticalMile>();
+        check::<Rankine>();
+        check::<Microvolt>();
+        check::<Megavolt>();
+        check::<Gigavolt>();
+    }
+}
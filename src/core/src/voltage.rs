@@ -0,0 +1,105 @@
+use super::prelude::{Family, Unit};
+
+/// Returns a Family that converts between units of voltage (e.g. mV, V, kV, etc.).
+///
+/// The megavolt symbol (`MV`) is case sensitive so it can be told apart from
+/// millivolts, which are matched by `mV` in any other case (e.g. `mv` or `Mv`).
+pub fn family() -> Family {
+    Family::new(
+        "Voltage",
+        "V",
+        vec![
+            Unit::new(vec!["volt", "volts"], "V", 1.0, 0.0)
+                .with_system("metric")
+                .with_description("the SI unit of electric potential"),
+            Unit::new(vec!["microvolt", "microvolts", "μv"], "uV", 1e-6, 0.0)
+                .with_system("metric")
+                .with_description("one millionth of a volt"),
+            Unit::new(vec!["millivolt", "millivolts"], "mV", 1e-3, 0.0)
+                .with_system("metric")
+                .with_description("one thousandth of a volt"),
+            Unit::new(vec!["kilovolt", "kilovolts"], "kV", 1e3, 0.0)
+                .with_system("metric")
+                .with_description("one thousand volts"),
+            Unit::new(vec!["megavolt", "megavolts"], "MV", 1e6, 0.0)
+                .with_case_sensitive_symbol("MV")
+                .with_system("metric")
+                .with_description("one million volts"),
+            Unit::new(vec!["gigavolt", "gigavolts"], "GV", 1e9, 0.0)
+                .with_system("metric")
+                .with_description("one billion volts"),
+        ],
+    )
+    .with_description("electric potential difference")
+    .with_metric(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::FamilyValidationError;
+    use crate::testutil::assertions::*;
+
+    /// Voltages are compared to a millionth of the unit.
+    const VOLTAGE_EPSILON: f64 = 1e-6;
+
+    #[test]
+    fn invariants() {
+        assert_family_invariants(&family());
+    }
+
+    #[test]
+    fn convert() {
+        let fam = family();
+        let cases = vec![
+            ("3.3v", "3.3v"),
+            ("5000mv", "5v"),
+            ("12000000uv", "12v"),
+            ("0.23kv", "230v"),
+            ("1.5MV", "1500000v"),
+            ("1gv", "1000000000v"),
+            ("1 μv", "0.000001v"),
+        ];
+
+        assert_identities(&fam, &cases);
+        assert_to_base_unit(&fam, &cases);
+        assert_from_base_unit(&fam, &cases);
+    }
+
+    #[test]
+    fn common_values() {
+        let fam = family();
+        assert_convert_eq(&fam, "1kv", "v", 1000.0);
+        assert_convert_eq(&fam, "1000mv", "v", 1.0);
+        assert_convert_eq(&fam, "3.3v", "mv", 3300.0);
+        assert_convert_eq(&fam, "230v", "kv", 0.23);
+        assert_convert_eq(&fam, "1GV", "kv", 1e6);
+        assert_convert_eq(&fam, "1MV", "v", 1e6);
+        assert_convert_eq(&fam, "2 megavolts", "kv", 2000.0);
+    }
+
+    #[test]
+    fn symmetry() {
+        assert_full_symmetry(&family(), VOLTAGE_EPSILON);
+    }
+
+    #[test]
+    fn megavolts_and_millivolts() {
+        let fam = family();
+        assert_eq!("MV", fam.unit_by_symbol("MV").unwrap().symbol);
+        assert_eq!("MV", fam.unit_by_symbol("Megavolts").unwrap().symbol);
+        for sym in ["mV", "mv", "Mv"] {
+            assert_eq!("mv", fam.unit_by_symbol(sym).unwrap().symbol, "{}", sym);
+        }
+
+        // Without the case sensitive symbol, the two still clash.
+        let mut units = family().into_units();
+        units.retain(|u| u.symbol != "MV");
+        units.push(Unit::new(vec!["megavolt", "megavolts"], "MV", 1e6, 0.0));
+
+        let errors = Family::new_unchecked("Voltage", "V", units)
+            .validate()
+            .unwrap_err();
+        assert!(errors.contains(&FamilyValidationError::DuplicateSymbol("mv".into())));
+    }
+}
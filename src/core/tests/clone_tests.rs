@@ -0,0 +1,157 @@
+use core::{
+    ConversionError, ConversionResult, CurrencyFamily, DisplayFormat, Family,
+    FamilyValidationError, Measurement, ParseValueError, PhysicalConstraint, RegistryError,
+    TableFormat, Unit, Value,
+};
+use std::collections::HashMap;
+
+fn widgets() -> Family {
+    Family::new(
+        "Clone Widgets",
+        "w",
+        vec![
+            Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0).with_description("one widget"),
+            Unit::new(vec!["kilowidget", "kilowidgets"], "kw", 1000.0, 0.0),
+        ],
+    )
+    .with_description("things made in a factory")
+}
+
+#[test]
+fn value() {
+    let original = Value::new(1.5, "km");
+    let mut clone = original.clone();
+    assert_eq!(original, clone);
+
+    clone.quantity = 3.0;
+    clone.unit.push('s');
+    assert_eq!(Value::new(1.5, "km"), original);
+    assert_ne!(original, clone);
+}
+
+#[test]
+fn unit() {
+    let original = Unit::new(vec!["widget", "widgets"], "w", 1.0, 0.0).with_system("metric");
+    let mut clone = original.clone();
+    assert!(original.structurally_eq(&clone));
+
+    clone.names.push("thingamajig".into());
+    clone.ratio = 2.0;
+    clone.system = None;
+    assert_eq!(2, original.names.len());
+    assert_eq!(1.0, original.ratio);
+    assert_eq!(Some("metric"), original.system.as_deref());
+
+    // Units with the same symbol are still equal, even though the fields differ.
+    assert_eq!(original, clone);
+    assert!(!original.structurally_eq(&clone));
+}
+
+#[test]
+fn family() {
+    let original = widgets();
+    let mut clone = original.clone();
+    assert_eq!(original, clone);
+    assert!(original
+        .units()
+        .iter()
+        .zip(clone.units())
+        .all(|(a, b)| a.structurally_eq(b)));
+
+    // The clone converts independently of the original.
+    clone.extend(vec![Unit::new(vec!["megawidget"], "mw", 1e6, 0.0)]);
+    clone.description = None;
+
+    let v = Value::new(2.0, "mw");
+    assert!(original.convert(v.clone(), "w").is_err());
+    assert_eq!(Value::new(2e6, "w"), clone.convert(v, "w").unwrap());
+    assert_eq!(
+        Some("things made in a factory"),
+        original.description.as_deref()
+    );
+
+    // The index is cloned too, so lookups still work on both.
+    assert_eq!("kw", original.unit_by_symbol("kilowidgets").unwrap().symbol);
+    assert_eq!("kw", clone.unit_by_symbol("kilowidgets").unwrap().symbol);
+}
+
+#[test]
+fn currency_family() {
+    let original = CurrencyFamily::new(HashMap::from([("EUR".to_string(), 0.5)])).unwrap();
+    let clone = original.clone();
+
+    assert_eq!(original.id(), clone.id());
+    assert_eq!(original.family(), clone.family());
+    assert_eq!(
+        original.convert(Value::new(1.0, "eur"), "usd"),
+        clone.convert(Value::new(1.0, "eur"), "usd")
+    );
+}
+
+#[test]
+fn conversion_result() {
+    let original = ConversionResult {
+        from: Value::new(1.0, "km"),
+        to: Value::new(1000.0, "m"),
+    };
+    let mut clone = original.clone();
+    assert_eq!(original, clone);
+
+    clone.to.quantity = 0.0;
+    assert_eq!(1000.0, original.to.quantity);
+    assert_ne!(original, clone);
+}
+
+#[test]
+fn errors() {
+    let err: ParseValueError = "abc".parse::<Value>().unwrap_err();
+    assert_eq!(err, err.clone());
+    assert_eq!(err.to_string(), err.clone().to_string());
+
+    let errors = [
+        ConversionError::unknown_unit("xyz"),
+        ConversionError::UnknownFamily("Widgets".into()),
+        ConversionError::Failed {
+            quantity: 1.0,
+            from: "m".into(),
+            to: "c".into(),
+        },
+        ConversionError::PhysicalConstraintViolated,
+        ConversionError::NonLinearConversion,
+        ConversionError::Overflow {
+            from: Value::new(1e308, "km"),
+            to_unit: "mm".into(),
+        },
+        ConversionError::Parse(err.clone()),
+        ConversionError::ChainStep {
+            step: 1,
+            to_unit: "cm".into(),
+            source: Box::new(ConversionError::NonLinearConversion),
+        },
+    ];
+    for err in &errors {
+        assert_eq!(err, &err.clone());
+    }
+
+    let errors = [
+        FamilyValidationError::BaseUnitNotFound,
+        FamilyValidationError::DuplicateSymbol("w".into()),
+    ];
+    for err in &errors {
+        assert_eq!(err, &err.clone());
+    }
+
+    let err = RegistryError::DuplicateId("Lengths".into());
+    assert_eq!(err, err.clone());
+}
+
+#[test]
+fn copy_types() {
+    let format = DisplayFormat::Precision(3);
+    assert_eq!(format, format.clone());
+    assert_eq!(TableFormat::Csv, TableFormat::Csv.clone());
+    assert_eq!(
+        PhysicalConstraint::NonNegative,
+        PhysicalConstraint::NonNegative.clone()
+    );
+}
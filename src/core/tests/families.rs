@@ -0,0 +1,30 @@
+use core::{UnitRegistry, Value};
+
+#[test]
+fn builtin_families_are_valid() {
+    for family in UnitRegistry::global().families() {
+        assert_eq!(Ok(()), family.validate(), "{} is invalid", family.id);
+    }
+}
+
+#[test]
+fn builtin_linear_units_round_trip_precisely() {
+    for family in UnitRegistry::global().families() {
+        let linear = family.units().iter().filter(|u| u.difference == 0.0);
+        for a in linear.clone() {
+            for b in linear.clone() {
+                let given = Value::new(12.5, &a.symbol);
+                let err = core::convert_roundtrip_error(given.clone(), &b.symbol).unwrap();
+
+                assert!(
+                    err < 1e-9,
+                    "{}: {} via {} ({})",
+                    family.id,
+                    given,
+                    b.symbol,
+                    err
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "length")]
+
+use core::{RegistryError, Unit, Value};
+
+#[test]
+fn register_rack_unit() -> core::Result {
+    let rack_unit = Unit::new(vec!["rack unit", "rack units"], "U", 0.04445, 0.0);
+    core::register_unit("Lengths", rack_unit.clone())?;
+
+    let v = core::convert(Value::new(42.0, "U"), "mm")?;
+    assert!((v.quantity - 1866.9).abs() < 1e-9);
+    assert_eq!("mm", v.unit);
+    assert!(core::units()["Lengths"].iter().any(|u| u.symbol == "u"));
+
+    assert_eq!(
+        Err(RegistryError::DuplicateSymbol("u".into())),
+        core::register_unit("Lengths", rack_unit)
+    );
+    assert_eq!(
+        Err(RegistryError::FamilyNotFound("Widgets".into())),
+        core::register_unit("Widgets", Unit::new(vec!["widget"], "w", 1.0, 0.0))
+    );
+
+    core::Value::ok()
+}